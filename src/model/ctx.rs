@@ -1,4 +1,8 @@
 use oxc::ast::ast::Program;
+use oxc::semantic::Semantic;
+
+use super::dom::Document;
+#[allow(clippy::enum_variant_names)]
 pub enum Ctx<'a> {
     JavaScriptCtx(JavaScriptCtx<'a>),
     CssCtx(CssCtx<'a>),
@@ -8,6 +12,8 @@ pub enum Ctx<'a> {
 pub struct JavaScriptCtx<'a> {
     pub input: &'a str,
     pub program: &'a Program<'a>,
+    /// scopes, symbols, references and the control flow graph of the program
+    pub semantic: &'a Semantic<'a>,
 }
 
 pub struct CssCtx<'a> {
@@ -15,5 +21,6 @@ pub struct CssCtx<'a> {
 }
 
 pub struct HtmlCtx<'a> {
-    pub input: &'a str,
+    /// the parsed elements with their positions in the input
+    pub document: &'a Document<'a>,
 }
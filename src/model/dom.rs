@@ -0,0 +1,457 @@
+// parses html into a tree of elements that keeps the byte offsets of every element and attribute
+// the parser is forgiving like a browser: unknown end tags are ignored and unclosed elements are closed at the end
+use std::ops::Range;
+
+/// elements that never have content or an end tag
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// elements whose content is raw text and is not parsed for tags
+const RAW_TEXT_ELEMENTS: [&str; 4] = ["script", "style", "textarea", "title"];
+
+/// elements that close an open `<p>` when they start
+const CLOSES_PARAGRAPH: [&str; 20] = [
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "div",
+    "dl",
+    "fieldset",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "main",
+    "nav",
+    "ol",
+    "p",
+];
+
+/// an attribute with its position in the input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attribute {
+    /// lowercase attribute name
+    pub name: String,
+    /// unquoted value, empty for boolean attributes like `hidden`
+    pub value: String,
+    /// byte range of the whole attribute, e.g. `src="a.png"`
+    pub span: Range<usize>,
+}
+
+/// an element of the document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Element {
+    /// index of the element in `Document::elements`
+    pub id: usize,
+    /// lowercase tag name
+    pub name: String,
+    pub attributes: Vec<Attribute>,
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+    /// number of ancestors, 0 for the root elements
+    pub depth: usize,
+    /// byte range of the start tag, e.g. `<img src="a.png">`
+    pub span: Range<usize>,
+    /// byte range of the raw text content of `<script>`, `<style>`, `<textarea>` and `<title>`
+    pub content: Option<Range<usize>>,
+    /// byte offset after the end tag, or where the element is closed implicitly
+    pub end: usize,
+}
+
+impl Element {
+    /// Get the value of the attribute with the given (lowercase) name
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.name == name)
+            .map(|attribute| attribute.value.as_str())
+    }
+
+    pub fn has_attr(&self, name: &str) -> bool {
+        self.attr(name).is_some()
+    }
+}
+
+/// the parsed document, elements are stored in source order
+#[derive(Debug)]
+pub struct Document<'a> {
+    pub input: &'a str,
+    pub elements: Vec<Element>,
+    /// byte offsets of the line starts, used to map offsets to lines and columns
+    line_starts: Vec<usize>,
+}
+
+impl<'a> Document<'a> {
+    pub fn parse(input: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(input.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        let mut document = Document {
+            input,
+            elements: vec![],
+            line_starts,
+        };
+        document.build_tree();
+        document
+    }
+
+    /// Get the line and column (both starting at 1) of a byte offset
+    pub fn position(&self, offset: usize) -> (i32, i32) {
+        let offset = offset.min(self.input.len());
+        let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+        let column = self
+            .input
+            .get(self.line_starts[line]..offset)
+            .map_or(0, |text| text.chars().count());
+        (line as i32 + 1, column as i32 + 1)
+    }
+
+    /// Get the parent, grandparent and so on of the element
+    pub fn ancestors<'s>(&'s self, element: &Element) -> impl Iterator<Item = &'s Element> {
+        std::iter::successors(
+            element.parent.map(|parent| &self.elements[parent]),
+            |element| element.parent.map(|parent| &self.elements[parent]),
+        )
+    }
+
+    /// Get the source text of the start tag
+    pub fn source(&self, element: &Element) -> &'a str {
+        self.input.get(element.span.clone()).unwrap_or_default()
+    }
+
+    /// Get the raw text content of a `<script>` or `<style>` element
+    pub fn content(&self, element: &Element) -> Option<&'a str> {
+        self.input.get(element.content.clone()?)
+    }
+
+    fn build_tree(&mut self) {
+        let input = self.input;
+        // ids of the open elements, innermost last
+        let mut open: Vec<usize> = vec![];
+        for tag in tags(input) {
+            match tag {
+                Tag::Start {
+                    name,
+                    attributes,
+                    span,
+                    self_closing,
+                } => {
+                    close_implied(&mut self.elements, &mut open, &name, span.start);
+                    let id = self.elements.len();
+                    let parent = open.last().copied();
+                    if let Some(parent) = parent {
+                        self.elements[parent].children.push(id);
+                    }
+                    let content = RAW_TEXT_ELEMENTS
+                        .contains(&name.as_str())
+                        .then(|| span.end..raw_text_end(input, span.end, &name));
+                    let is_void = VOID_ELEMENTS.contains(&name.as_str()) || self_closing;
+                    let end = span.end;
+                    self.elements.push(Element {
+                        id,
+                        name,
+                        attributes,
+                        parent,
+                        children: vec![],
+                        depth: open.len(),
+                        span,
+                        content,
+                        end,
+                    });
+                    if !is_void {
+                        open.push(id);
+                    }
+                }
+                Tag::End { name, span } => {
+                    // close the innermost open element with this name and everything opened inside it
+                    if let Some(index) = open.iter().rposition(|id| self.elements[*id].name == name)
+                    {
+                        for id in open.drain(index + 1..) {
+                            self.elements[id].end = span.start;
+                        }
+                        self.elements[open[index]].end = span.end;
+                        open.truncate(index);
+                    }
+                }
+            }
+        }
+        for id in open {
+            self.elements[id].end = input.len();
+        }
+    }
+}
+
+/// a start or end tag with its position in the input
+#[derive(Debug)]
+enum Tag {
+    Start {
+        name: String,
+        attributes: Vec<Attribute>,
+        span: Range<usize>,
+        self_closing: bool,
+    },
+    End {
+        name: String,
+        span: Range<usize>,
+    },
+}
+
+// region: helpers
+
+/// Close the open elements that end implicitly when the new element starts, e.g. `<li>` after `<li>`
+/// `at` is the offset of the new start tag, where the closed elements end
+fn close_implied(elements: &mut [Element], open: &mut Vec<usize>, name: &str, at: usize) {
+    let Some(current) = open.last().map(|id| elements[*id].name.as_str()) else {
+        return;
+    };
+    let implied = match name {
+        "li" => current == "li",
+        "option" => current == "option",
+        "dt" | "dd" => matches!(current, "dt" | "dd"),
+        "tr" => matches!(current, "tr" | "td" | "th"),
+        "td" | "th" => matches!(current, "td" | "th"),
+        _ => current == "p" && CLOSES_PARAGRAPH.contains(&name),
+    };
+    if !implied {
+        return;
+    }
+    let mut closed = open.pop().into_iter().collect::<Vec<_>>();
+    // `<tr>` after a cell closes the cell and its row
+    if name == "tr" && open.last().is_some_and(|id| elements[*id].name == "tr") {
+        closed.extend(open.pop());
+    }
+    for id in closed {
+        elements[id].end = at;
+    }
+}
+
+/// Split the html into its start and end tags, keeping the byte offsets
+/// comments, doctypes and the content of raw text elements like `<script>` are skipped
+fn tags(input: &str) -> Vec<Tag> {
+    let bytes = input.as_bytes();
+    let mut tags = vec![];
+    let mut pos = 0;
+    while let Some(offset) = input[pos..].find('<') {
+        let start = pos + offset;
+        let rest = &input[start..];
+        if rest.starts_with("<!--") {
+            pos = rest
+                .find("-->")
+                .map_or(input.len(), |end| start + end + "-->".len());
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            pos = skip_to_tag_end(input, start);
+        } else if rest.starts_with("</") {
+            let name = tag_name(&input[start + 2..]);
+            pos = skip_to_tag_end(input, start);
+            if !name.is_empty() {
+                tags.push(Tag::End {
+                    name,
+                    span: start..pos,
+                });
+            }
+        } else if bytes
+            .get(start + 1)
+            .is_some_and(|byte| byte.is_ascii_alphabetic())
+        {
+            let tag = start_tag(input, start);
+            if let Tag::Start { name, span, .. } = &tag {
+                pos = span.end;
+                if RAW_TEXT_ELEMENTS.contains(&name.as_str()) {
+                    pos = raw_text_end(input, pos, name);
+                }
+            }
+            tags.push(tag);
+        } else {
+            pos = start + 1;
+        }
+    }
+    tags
+}
+
+/// Parse the start tag at the given offset
+fn start_tag(input: &str, start: usize) -> Tag {
+    let bytes = input.as_bytes();
+    let name = tag_name(&input[start + 1..]);
+    let mut pos = start + 1 + name.len();
+    let mut attributes = vec![];
+    let mut self_closing = false;
+    loop {
+        while pos < bytes.len() && (bytes[pos].is_ascii_whitespace() || bytes[pos] == b'/') {
+            self_closing = bytes[pos] == b'/';
+            pos += 1;
+        }
+        if pos >= bytes.len() {
+            break;
+        }
+        if bytes[pos] == b'>' {
+            pos += 1;
+            break;
+        }
+        self_closing = false;
+        let name_start = pos;
+        while pos < bytes.len()
+            && !bytes[pos].is_ascii_whitespace()
+            && !matches!(bytes[pos], b'=' | b'>' | b'/')
+        {
+            pos += 1;
+        }
+        let attribute = input[name_start..pos].to_ascii_lowercase();
+        let mut attribute_end = pos;
+        while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        let mut value = String::new();
+        if bytes.get(pos) == Some(&b'=') {
+            pos += 1;
+            while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+                pos += 1;
+            }
+            match bytes.get(pos) {
+                Some(&quote @ (b'"' | b'\'')) => {
+                    let value_start = pos + 1;
+                    let value_end = input[value_start..]
+                        .find(quote as char)
+                        .map_or(input.len(), |end| value_start + end);
+                    value = input[value_start..value_end].to_string();
+                    pos = (value_end + 1).min(input.len());
+                }
+                _ => {
+                    let value_start = pos;
+                    while pos < bytes.len()
+                        && !bytes[pos].is_ascii_whitespace()
+                        && bytes[pos] != b'>'
+                    {
+                        pos += 1;
+                    }
+                    value = input[value_start..pos].to_string();
+                }
+            }
+            attribute_end = pos;
+        }
+        if !attribute.is_empty() {
+            attributes.push(Attribute {
+                name: attribute,
+                value,
+                span: name_start..attribute_end,
+            });
+        }
+    }
+    Tag::Start {
+        name,
+        attributes,
+        span: start..pos,
+        self_closing,
+    }
+}
+
+/// Get the lowercase tag name at the start of the input
+fn tag_name(input: &str) -> String {
+    input
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | ':'))
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+/// Get the offset after the next `>`
+fn skip_to_tag_end(input: &str, start: usize) -> usize {
+    input[start..]
+        .find('>')
+        .map_or(input.len(), |end| start + end + 1)
+}
+
+/// Get the offset of the end tag closing the raw text element
+fn raw_text_end(input: &str, start: usize, name: &str) -> usize {
+    let mut pos = start;
+    while let Some(offset) = input[pos..].find("</") {
+        let candidate = pos + offset;
+        if input
+            .get(candidate + 2..candidate + 2 + name.len())
+            .is_some_and(|closing| closing.eq_ignore_ascii_case(name))
+        {
+            return candidate;
+        }
+        pos = candidate + 2;
+    }
+    input.len()
+}
+
+// endregion: helpers
+// region: tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elements_and_attributes_keep_their_offsets() {
+        let input = "<!DOCTYPE html>\n<p class=intro>Hi <IMG SRC='a.png' alt=\"a > b\" hidden></p>";
+        let document = Document::parse(input);
+        assert_eq!(document.elements.len(), 2);
+        let image = &document.elements[1];
+        assert_eq!(image.name, "img");
+        assert_eq!(image.parent, Some(0));
+        assert_eq!(
+            document.source(image),
+            "<IMG SRC='a.png' alt=\"a > b\" hidden>"
+        );
+        assert_eq!(image.attr("src"), Some("a.png"));
+        assert_eq!(image.attr("alt"), Some("a > b"));
+        assert!(image.has_attr("hidden"));
+        assert_eq!(&input[image.attributes[1].span.clone()], "alt=\"a > b\"");
+        assert_eq!(document.position(image.span.start), (2, 19));
+    }
+
+    #[test]
+    fn test_comments_and_raw_text_are_not_parsed() {
+        let input =
+            "<!-- <img src=a.png> --><script>if (a < b) document.write('<img>');</script><br/>";
+        let document = Document::parse(input);
+        let names: Vec<&str> = document
+            .elements
+            .iter()
+            .map(|element| element.name.as_str())
+            .collect();
+        assert_eq!(names, ["script", "br"]);
+        assert!(document.elements[0].children.is_empty());
+        assert_eq!(
+            document.content(&document.elements[0]),
+            Some("if (a < b) document.write('<img>');")
+        );
+        assert_eq!(document.elements[1].parent, None);
+    }
+
+    #[test]
+    fn test_tree_is_built_like_a_browser() {
+        let input =
+            "<html><body><ul><li>one<li>two</ul><p>text<div><span></div><svg><path/></svg></body>";
+        let document = Document::parse(input);
+        let named = |name: &'static str| document.elements.iter().filter(move |e| e.name == name);
+        let depth = |name: &'static str| named(name).next().map(|e| e.depth);
+        assert_eq!(named("li").map(|e| e.depth).collect::<Vec<_>>(), [3, 3]);
+        assert_eq!(depth("p"), Some(2));
+        // `<div>` closes the paragraph, `</div>` closes the unclosed span
+        assert_eq!(depth("div"), Some(2));
+        assert_eq!(depth("svg"), Some(2));
+        assert_eq!(depth("path"), Some(3));
+        let path = named("path").next().unwrap();
+        let ancestors: Vec<&str> = document.ancestors(path).map(|e| e.name.as_str()).collect();
+        assert_eq!(ancestors, ["svg", "body", "html"]);
+        let source = |name: &'static str| {
+            let element = named(name).next().unwrap();
+            &input[element.span.start..element.end]
+        };
+        assert_eq!(source("svg"), "<svg><path/></svg>");
+        assert_eq!(source("li"), "<li>one");
+        assert_eq!(source("span"), "<span>");
+        assert!(source("html").ends_with("</body>"));
+    }
+}
+// endregion: tests
@@ -2,10 +2,12 @@
 // LinterResult is a struct that contains the result of the linter
 // each line is parsed and the result is stored in the LinterResult
 use crate::model::ctx::*;
+use crate::model::dom::Document;
 use crate::model::rules::*;
 use oxc::allocator::Allocator;
 
 use oxc::parser::Parser;
+use oxc::semantic::SemanticBuilder;
 use oxc::span::SourceType;
 
 /// parse_code parses the code and returns the linter result
@@ -30,23 +32,33 @@ fn handle_js(code: &str, rules_to_apply: Vec<String>) -> Vec<LineResult> {
     let source_type = SourceType::from_path("javscript.js").unwrap();
     let ret = Parser::new(&allocator, code, source_type).parse();
     let program = ret.program;
+    // scopes, symbols and references are built once and shared by all rules
+    // the control flow graph is what the dead code rule uses to find unreachable statements
+    let semantic = SemanticBuilder::new(code, source_type)
+        .with_cfg(true)
+        .build(&program)
+        .semantic;
     let ctx = Ctx::JavaScriptCtx(JavaScriptCtx {
-        input: &code,
+        input: code,
         program: &program,
+        semantic: &semantic,
     });
     let js_rules: Vec<Box<dyn Rule>> = load_js_rules(rules_to_apply);
     apply_rules(js_rules, &ctx)
 }
 
 fn handle_html(code: &str, rules_to_apply: Vec<String>) -> Vec<LineResult> {
-    let ctx = Ctx::HtmlCtx(HtmlCtx { input: &code });
+    let document = Document::parse(code);
+    let ctx = Ctx::HtmlCtx(HtmlCtx {
+        document: &document,
+    });
 
     let html_rules: Vec<Box<dyn Rule>> = load_html_rules(rules_to_apply);
     apply_rules(html_rules, &ctx)
 }
 
 fn handle_css(code: &str, rules_to_apply: Vec<String>) -> Vec<LineResult> {
-    let ctx = Ctx::CssCtx(CssCtx { input: &code });
+    let ctx = Ctx::CssCtx(CssCtx { input: code });
     let css_rules: Vec<Box<dyn Rule>> = load_css_rules(rules_to_apply);
     apply_rules(css_rules, &ctx)
 }
@@ -1,3 +1,4 @@
 pub mod ctx;
+pub mod dom;
 pub mod linter;
 pub mod rules;
@@ -22,7 +22,7 @@ impl Rule for Minify {
                 .minify(css_ctx.input, Level::Three)
                 .unwrap_or_default();
             // check if the minified version is different from the input
-            if minified != css_ctx.input.to_string() {
+            if minified != css_ctx.input {
                 // return the line result
                 return Some(vec![LineResult {
                     severity: crate::model::rules::Severity::Info,
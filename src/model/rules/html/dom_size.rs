@@ -0,0 +1,224 @@
+use crate::model::ctx::Ctx;
+use crate::model::rules::LineResult;
+use crate::model::rules::Severity;
+
+use crate::model::dom::{Document, Element};
+
+use super::{element_result, Rule};
+
+/// This rule is used to find documents with too many elements, too deep nesting or too many children per parent
+#[derive(Debug)]
+pub struct DomSize {
+    pub max_elements: usize,
+    pub max_depth: usize,
+    pub max_children: usize,
+    /// number of largest subtrees listed when the document has too many elements
+    pub largest_subtrees: usize,
+}
+
+impl Default for DomSize {
+    fn default() -> Self {
+        DomSize {
+            max_elements: 1400,
+            max_depth: 32,
+            max_children: 60,
+            largest_subtrees: 3,
+        }
+    }
+}
+
+impl Rule for DomSize {
+    fn get_name(&self) -> &str {
+        "DOM-Size"
+    }
+
+    fn get_description(&self) -> &str {
+        "a large DOM uses more memory and makes every style recalculation and layout slower."
+    }
+
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        if let Ctx::HtmlCtx(html_ctx) = ctx {
+            let document = html_ctx.document;
+            let mut result = vec![];
+            let total = document.elements.len();
+
+            if total > self.max_elements {
+                let body = document
+                    .elements
+                    .iter()
+                    .find(|element| element.name == "body")
+                    .or(document.elements.first());
+                if let Some(body) = body {
+                    let message = format!(
+                        "the document has {} elements, more than {}. render long lists and hidden content only when needed",
+                        total, self.max_elements
+                    );
+                    result.push(element_result(
+                        self,
+                        document,
+                        body,
+                        Severity::Warning,
+                        &message,
+                    ));
+                }
+                for (element, size) in self.find_largest_subtrees(document) {
+                    let message = format!(
+                        "contains {} of the {} elements of the document",
+                        size, total
+                    );
+                    result.push(element_result(
+                        self,
+                        document,
+                        element,
+                        Severity::Info,
+                        &message,
+                    ));
+                }
+            }
+
+            // the depth of an element counts the element itself
+            if let Some(deepest) = document.elements.iter().max_by_key(|element| element.depth) {
+                if deepest.depth + 1 > self.max_depth {
+                    let message = format!(
+                        "is nested {} levels deep, more than {}. flatten wrapper elements",
+                        deepest.depth + 1,
+                        self.max_depth
+                    );
+                    result.push(element_result(
+                        self,
+                        document,
+                        deepest,
+                        Severity::Warning,
+                        &message,
+                    ));
+                }
+            }
+
+            for parent in &document.elements {
+                if parent.children.len() > self.max_children {
+                    let message = format!(
+                        "has {} child elements, more than {}. paginate or virtualize the list",
+                        parent.children.len(),
+                        self.max_children
+                    );
+                    result.push(element_result(
+                        self,
+                        document,
+                        parent,
+                        Severity::Warning,
+                        &message,
+                    ));
+                }
+            }
+
+            result.sort_by_key(|line_result| (line_result.line, line_result.column));
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+impl DomSize {
+    /// Find the largest subtrees that do not contain each other, with their number of elements
+    /// `<html>`, `<head>` and `<body>` contain everything and are skipped
+    fn find_largest_subtrees<'d>(&self, document: &'d Document<'_>) -> Vec<(&'d Element, usize)> {
+        // children always come after their parent, so the sizes can be summed up from the end
+        let mut sizes = vec![1; document.elements.len()];
+        for element in document.elements.iter().rev() {
+            if let Some(parent) = element.parent {
+                sizes[parent] += sizes[element.id];
+            }
+        }
+
+        let mut candidates: Vec<&Element> = document
+            .elements
+            .iter()
+            .filter(|element| !matches!(element.name.as_str(), "html" | "head" | "body"))
+            .collect();
+        candidates.sort_by_key(|element| std::cmp::Reverse(sizes[element.id]));
+
+        let mut largest: Vec<(&Element, usize)> = vec![];
+        for candidate in candidates {
+            if largest.len() == self.largest_subtrees {
+                break;
+            }
+            let overlaps = largest.iter().any(|(picked, _)| {
+                document
+                    .ancestors(candidate)
+                    .any(|ancestor| ancestor.id == picked.id)
+                    || document
+                        .ancestors(picked)
+                        .any(|ancestor| ancestor.id == candidate.id)
+            });
+            if !overlaps {
+                largest.push((candidate, sizes[candidate.id]));
+            }
+        }
+        largest
+    }
+}
+
+// region: tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::rules::html::apply_to_source;
+
+    #[test]
+    fn test_large_document_points_to_largest_subtrees() {
+        let rows = "<tr><td>a</td><td>b</td></tr>".repeat(20);
+        let html = format!(
+            "<html><body>\n<nav><a>home</a></nav>\n<table>{}</table>\n<ul><li>a<li>b<li>c</ul>\n</body></html>",
+            rows
+        );
+        let rule = DomSize {
+            max_elements: 50,
+            ..DomSize::default()
+        };
+        let result = apply_to_source(&rule, &html);
+        assert_eq!(result.len(), 4);
+        assert!(result[0]
+            .description
+            .contains("the document has 69 elements, more than 50"));
+        assert_eq!(result[1].line, 2);
+        assert!(result[1]
+            .description
+            .starts_with("'<nav>' contains 2 of the 69 elements"));
+        assert_eq!(result[2].line, 3);
+        assert!(result[2]
+            .description
+            .starts_with("'<table>' contains 61 of the 69 elements"));
+        assert_eq!(result[3].line, 4);
+    }
+
+    #[test]
+    fn test_deep_nesting_and_wide_parents() {
+        let html = format!(
+            "<div>{}<span>deep</span>{}</div>\n<select>{}</select>",
+            "<div>".repeat(5),
+            "</div>".repeat(5),
+            "<option>x".repeat(4)
+        );
+        let rule = DomSize {
+            max_depth: 5,
+            max_children: 3,
+            ..DomSize::default()
+        };
+        let result = apply_to_source(&rule, &html);
+        assert_eq!(result.len(), 2);
+        assert!(result[0]
+            .description
+            .starts_with("'<span>' is nested 7 levels deep, more than 5"));
+        assert_eq!(result[1].line, 2);
+        assert!(result[1].description.contains("has 4 child elements"));
+    }
+
+    #[test]
+    fn test_small_documents_are_ignored() {
+        let html = include_str!("../../../../testfiles/img_iframe_html_test");
+        let result = apply_to_source(&DomSize::default(), html);
+        assert!(result.is_empty());
+    }
+}
+// endregion: tests
@@ -0,0 +1,141 @@
+use crate::model::ctx::Ctx;
+use crate::model::rules::LineResult;
+use crate::model::rules::Severity;
+
+use super::{element_result, Rule};
+
+/// a heavyweight embed that can be replaced with a facade until the user interacts with it
+struct Embed {
+    name: &'static str,
+    /// parts of the iframe url that identify the embed
+    urls: &'static [&'static str],
+    /// rough size of the scripts, styles and images the embed loads before it is used
+    kilobytes: u32,
+    facade: &'static str,
+}
+
+/// the recognized embeds, the sizes are rounded transfer sizes of an empty player, map or post
+const EMBEDS: [Embed; 4] = [
+    Embed {
+        name: "YouTube",
+        urls: &["youtube.com/embed/", "youtube-nocookie.com/embed/"],
+        kilobytes: 1000,
+        facade: "show the thumbnail from i.ytimg.com with a play button, or use lite-youtube-embed",
+    },
+    Embed {
+        name: "Vimeo",
+        urls: &["player.vimeo.com/video/"],
+        kilobytes: 500,
+        facade: "show the video thumbnail with a play button, or use lite-vimeo-embed",
+    },
+    Embed {
+        name: "Google Maps",
+        urls: &["google.com/maps/embed", "maps.google.com/maps"],
+        kilobytes: 600,
+        facade: "show a static map image that links to the map or loads it on click",
+    },
+    Embed {
+        name: "Twitter",
+        urls: &["platform.twitter.com/embed/", "twitframe.com/"],
+        kilobytes: 400,
+        facade: "show the post as a static blockquote or screenshot that links to it",
+    },
+];
+
+/// This rule is used to find video, map and social embeds that could be loaded on click
+pub struct EmbedFacades;
+
+impl Rule for EmbedFacades {
+    fn get_name(&self) -> &str {
+        "Embed-Facades"
+    }
+
+    fn get_description(&self) -> &str {
+        "embedded players, maps and posts load their scripts even if the visitor never uses them. a facade looks the same and loads the embed only on click."
+    }
+
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        if let Ctx::HtmlCtx(html_ctx) = ctx {
+            let document = html_ctx.document;
+            let mut result = vec![];
+            for iframe in document
+                .elements
+                .iter()
+                .filter(|element| element.name == "iframe")
+            {
+                let Some(embed) = iframe.attr("src").and_then(embed) else {
+                    continue;
+                };
+                let message = format!(
+                    "is a {} embed that loads about {} KB before it is used. {}",
+                    embed.name, embed.kilobytes, embed.facade
+                );
+                result.push(element_result(
+                    self,
+                    document,
+                    iframe,
+                    Severity::Warning,
+                    &message,
+                ));
+            }
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+// region: helpers
+
+/// Get the embed loaded by the iframe url
+fn embed(src: &str) -> Option<&'static Embed> {
+    let src = src.trim().to_ascii_lowercase();
+    EMBEDS
+        .iter()
+        .find(|embed| embed.urls.iter().any(|url| src.contains(url)))
+}
+
+// endregion: helpers
+// region: tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::rules::html::apply_to_source;
+
+    #[test]
+    fn test_video_embeds() {
+        let html = "<iframe width=\"560\" height=\"315\" src=\"https://www.youtube.com/embed/dQw4w9WgXcQ\" allowfullscreen></iframe>
+<iframe src=\"https://www.YouTube-nocookie.com/embed/dQw4w9WgXcQ?autoplay=0\" loading=\"lazy\"></iframe>
+<iframe src=\"https://player.vimeo.com/video/76979871\"></iframe>";
+        let result = apply_to_source(&EmbedFacades, html);
+        assert_eq!(result.len(), 3);
+        assert!(result[0]
+            .description
+            .contains("is a YouTube embed that loads about 1000 KB before it is used"));
+        assert_eq!(result[1].line, 2);
+        assert!(result[2].description.contains("lite-vimeo-embed"));
+    }
+
+    #[test]
+    fn test_map_and_social_embeds() {
+        let html = "<iframe src=\"https://www.google.com/maps/embed?pb=!1m18\"></iframe>
+<iframe src=\"https://platform.twitter.com/embed/Tweet.html?id=20\"></iframe>";
+        let result = apply_to_source(&EmbedFacades, html);
+        assert_eq!(result.len(), 2);
+        assert!(result[0].description.contains("Google Maps embed"));
+        assert!(result[1].description.contains("Twitter embed"));
+    }
+
+    #[test]
+    fn test_other_iframes_are_ignored() {
+        let html = include_str!("../../../../testfiles/img_iframe_html_test");
+        assert!(html.contains("<iframe"));
+        let html = format!(
+            "{}<iframe src=\"https://www.youtube.com/watch?v=1\"></iframe><iframe></iframe>",
+            html
+        );
+        let result = apply_to_source(&EmbedFacades, &html);
+        assert!(result.is_empty());
+    }
+}
+// endregion: tests
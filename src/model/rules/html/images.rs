@@ -0,0 +1,294 @@
+use crate::model::ctx::Ctx;
+use crate::model::rules::LineResult;
+use crate::model::rules::Severity;
+
+use crate::model::dom::{Document, Element};
+
+use super::{element_result, url_extension, Rule};
+
+/// image formats that have smaller modern alternatives, GIFs are left to the Animated-GIF rule
+const LEGACY_FORMATS: [&str; 4] = ["png", "jpg", "jpeg", "bmp"];
+
+/// This rule is used to find PNG, JPEG and BMP images that are not offered as WebP or AVIF
+pub struct ModernImageFormats;
+
+impl Rule for ModernImageFormats {
+    fn get_name(&self) -> &str {
+        "Modern-Image-Formats"
+    }
+
+    fn get_description(&self) -> &str {
+        "WebP and AVIF images are usually 25-50% smaller than PNG, JPEG and BMP images of the same quality."
+    }
+
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        if let Ctx::HtmlCtx(html_ctx) = ctx {
+            let document = html_ctx.document;
+            let mut result = vec![];
+            for image in images(document) {
+                let Some(format) = image.attr("src").and_then(legacy_format) else {
+                    continue;
+                };
+                if offers_modern_format(document, image) {
+                    continue;
+                }
+                let message = format!(
+                    "is a {} image without a WebP or AVIF alternative. wrap it in a <picture> with a <source type=\"image/avif\"> or <source type=\"image/webp\">",
+                    format.to_ascii_uppercase()
+                );
+                result.push(element_result(
+                    self,
+                    document,
+                    image,
+                    Severity::Info,
+                    &message,
+                ));
+            }
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+/// This rule is used to find images without width and height, which shift the layout when they load
+pub struct ImageDimensions;
+
+impl Rule for ImageDimensions {
+    fn get_name(&self) -> &str {
+        "Image-Dimensions"
+    }
+
+    fn get_description(&self) -> &str {
+        "without dimensions the browser cannot reserve space for an image, so the content below it moves when it loads."
+    }
+
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        if let Ctx::HtmlCtx(html_ctx) = ctx {
+            let document = html_ctx.document;
+            let mut result = vec![];
+            for image in images(document) {
+                if image.has_attr("hidden") || has_sized_style(image) {
+                    continue;
+                }
+                let missing = match (image.has_attr("width"), image.has_attr("height")) {
+                    (true, true) => continue,
+                    (true, false) => "height attribute",
+                    (false, true) => "width attribute",
+                    (false, false) => "width and height attributes",
+                };
+                let message = format!(
+                    "has no {}. set the intrinsic size of the image, css can still scale it",
+                    missing
+                );
+                result.push(element_result(
+                    self,
+                    document,
+                    image,
+                    Severity::Warning,
+                    &message,
+                ));
+            }
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+/// This rule is used to find large images that are downloaded in full size on every screen
+#[derive(Debug)]
+pub struct ResponsiveImages {
+    /// images with a width attribute of at least this many pixels should offer smaller sizes
+    pub min_width: u32,
+}
+
+impl Default for ResponsiveImages {
+    fn default() -> Self {
+        ResponsiveImages { min_width: 600 }
+    }
+}
+
+impl Rule for ResponsiveImages {
+    fn get_name(&self) -> &str {
+        "Responsive-Images"
+    }
+
+    fn get_description(&self) -> &str {
+        "with srcset and sizes the browser picks the image size matching the screen instead of downloading the largest one."
+    }
+
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        if let Ctx::HtmlCtx(html_ctx) = ctx {
+            let document = html_ctx.document;
+            let mut result = vec![];
+            for image in images(document) {
+                let message = match image.attr("srcset") {
+                    Some(srcset) if has_width_descriptors(srcset) && !image.has_attr("sizes") => {
+                        "uses width descriptors in srcset without sizes, so the browser assumes the image is as wide as the viewport. add sizes with the rendered width".to_string()
+                    }
+                    Some(_) => continue,
+                    None => {
+                        let Some(width) = image.attr("width").and_then(pixels) else {
+                            continue;
+                        };
+                        let is_vector = image
+                            .attr("src")
+                            .is_some_and(|src| url_extension(src).as_deref() == Some("svg"));
+                        if width < self.min_width
+                            || is_vector
+                            || picture_sources(document, image).any(|source| source.has_attr("srcset"))
+                        {
+                            continue;
+                        }
+                        format!(
+                            "is {}px wide but has no srcset, so small screens download the full-size image. add smaller versions with srcset and sizes",
+                            width
+                        )
+                    }
+                };
+                result.push(element_result(
+                    self,
+                    document,
+                    image,
+                    Severity::Info,
+                    &message,
+                ));
+            }
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+// region: helpers
+
+fn images<'d>(document: &'d Document<'_>) -> impl Iterator<Item = &'d Element> {
+    document
+        .elements
+        .iter()
+        .filter(|element| element.name == "img")
+}
+
+/// Get the `<source>` elements of the `<picture>` around the image
+fn picture_sources<'d>(
+    document: &'d Document<'_>,
+    image: &Element,
+) -> impl Iterator<Item = &'d Element> {
+    image
+        .parent
+        .map(|parent| &document.elements[parent])
+        .filter(|parent| parent.name == "picture")
+        .into_iter()
+        .flat_map(|picture| picture.children.iter())
+        .map(|child| &document.elements[*child])
+        .filter(|child| child.name == "source")
+}
+
+/// Check if the image itself or its `<picture>` offers a WebP or AVIF version
+fn offers_modern_format(document: &Document<'_>, image: &Element) -> bool {
+    let is_modern = |value: &str| {
+        let value = value.to_ascii_lowercase();
+        value.contains("webp") || value.contains("avif")
+    };
+    image.attr("srcset").is_some_and(is_modern)
+        || picture_sources(document, image).any(|source| {
+            source.attr("type").is_some_and(is_modern)
+                || source.attr("srcset").is_some_and(is_modern)
+        })
+}
+
+/// Get the legacy format of the image url, data urls are ignored
+fn legacy_format(url: &str) -> Option<String> {
+    if url.trim_start().starts_with("data:") {
+        return None;
+    }
+    url_extension(url).filter(|extension| LEGACY_FORMATS.contains(&extension.as_str()))
+}
+
+/// Check if the srcset lists candidates like `hero-800.jpg 800w`
+fn has_width_descriptors(srcset: &str) -> bool {
+    srcset.split(',').any(|candidate| {
+        candidate
+            .split_whitespace()
+            .nth(1)
+            .is_some_and(|descriptor| descriptor.ends_with(['w', 'W']))
+    })
+}
+
+/// Check if the inline style already sizes the image
+fn has_sized_style(image: &Element) -> bool {
+    let Some(style) = image.attr("style") else {
+        return false;
+    };
+    let style = style.to_ascii_lowercase();
+    let declares = |property: &str| {
+        style
+            .split(';')
+            .any(|declaration| declaration.split(':').next().map(str::trim) == Some(property))
+    };
+    declares("aspect-ratio") || (declares("width") && declares("height"))
+}
+
+/// Parse a width attribute like `800` or `800px`
+fn pixels(value: &str) -> Option<u32> {
+    value.trim().trim_end_matches("px").parse().ok()
+}
+
+// endregion: helpers
+// region: tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::rules::html::apply_to_source;
+
+    #[test]
+    fn test_legacy_formats_without_modern_alternative() {
+        let html = "<img src=\"/img/hero.JPG?v=2\" width=\"800\" height=\"400\">
+<picture><source srcset=\"team.avif\" type=\"image/avif\"><img src=\"team.png\"></picture>
+<img src=\"logo.svg\"><img src=\"data:image/png;base64,iVBORw0KGgo=\">
+<img src=\"photo.jpg\" srcset=\"photo.webp 1x\"><img src=\"icon.bmp\">";
+        let result = apply_to_source(&ModernImageFormats, html);
+        assert_eq!(result.len(), 2);
+        assert_eq!((result[0].line, result[0].column), (1, 1));
+        assert!(result[0].description.contains("is a JPG image"));
+        assert_eq!(result[1].line, 4);
+        assert!(result[1]
+            .description
+            .starts_with("'<img src=\"icon.bmp\">'"));
+    }
+
+    #[test]
+    fn test_images_without_dimensions() {
+        let html = "<img src=\"a.png\" width=\"10\" height=\"10\">
+<img src=\"b.png\" width=\"10\">
+<img src=\"c.png\" style=\"width: 100%; aspect-ratio: 16 / 9\">
+<img src=\"d.png\" hidden>
+<img src=\"e.png\">";
+        let result = apply_to_source(&ImageDimensions, html);
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].line, 2);
+        assert!(result[0].description.contains("has no height attribute"));
+        assert_eq!(result[1].line, 5);
+        assert!(result[1]
+            .description
+            .contains("has no width and height attributes"));
+    }
+
+    #[test]
+    fn test_large_images_without_srcset_or_sizes() {
+        let html = "<img src=\"hero.jpg\" width=\"1200\" height=\"600\">
+<img src=\"icon.png\" width=\"32\" height=\"32\">
+<img src=\"map.svg\" width=\"900\">
+<img src=\"a.jpg\" srcset=\"a-400.jpg 400w, a-800.jpg 800w\">
+<img src=\"b.jpg\" srcset=\"b-400.jpg 400w, b-800.jpg 800w\" sizes=\"50vw\">
+<img src=\"c.jpg\" srcset=\"c@2x.jpg 2x\" width=\"700\">";
+        let result = apply_to_source(&ResponsiveImages::default(), html);
+        assert_eq!(result.len(), 2);
+        assert!(result[0].description.contains("is 1200px wide"));
+        assert_eq!(result[1].line, 4);
+        assert!(result[1].description.contains("without sizes"));
+    }
+}
+// endregion: tests
@@ -1,13 +1,27 @@
 use crate::model::rules::LineResult;
-use ::scraper::{Html, Selector};
 
 use crate::model::ctx::Ctx;
 
 use crate::model::rules::Severity;
 
-use super::Rule;
+use crate::model::dom::{Document, Element};
 
-pub struct Loading;
+use super::{element_result, Rule};
+
+/// This rule is used to find images, iframes and video posters that are not lazy-loaded
+#[derive(Debug)]
+pub struct Loading {
+    /// the first images of the page are probably visible without scrolling and should be loaded eagerly
+    pub above_fold_images: usize,
+}
+
+impl Default for Loading {
+    fn default() -> Self {
+        Loading {
+            above_fold_images: 2,
+        }
+    }
+}
 
 impl Rule for Loading {
     fn get_name(&self) -> &str {
@@ -16,56 +30,152 @@ impl Rule for Loading {
 
     fn get_description(&self) -> &str {
         // TODO add link to lazy loading
-        "consinder lazy-loading images and scripts to improve performance."
+        "consider lazy-loading images and iframes to improve performance."
     }
 
     fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
         if let Ctx::HtmlCtx(html_ctx) = ctx {
-            Some(parse_lazy_loading(html_ctx.input))
+            Some(self.parse_lazy_loading(html_ctx.document))
         } else {
             None
         }
     }
 }
 
-pub fn parse_lazy_loading(html: &str) -> Vec<LineResult> {
-    let fragment = Html::parse_document(html);
-    let mut result = lazy_loading_selector(&fragment, html, "img");
-    let result2 = lazy_loading_selector(&fragment, html, "iframe");
-    // join the two results
-    result.extend(result2);
-    result
+impl Loading {
+    pub fn parse_lazy_loading(&self, document: &Document<'_>) -> Vec<LineResult> {
+        let mut result = vec![];
+        // number of images and video posters before the current element
+        let mut images = 0;
+        for element in &document.elements {
+            let above_fold = images < self.above_fold_images;
+            let finding = match element.name.as_str() {
+                "img" => {
+                    images += 1;
+                    let in_picture = document
+                        .ancestors(element)
+                        .any(|ancestor| ancestor.name == "picture");
+                    self.check_image(element, above_fold, in_picture)
+                }
+                "iframe" => match loading(element) {
+                    Some("lazy" | "eager") => None,
+                    _ => Some((Severity::Warning, "is loaded together with the page. add loading=\"lazy\" so it is only loaded when it scrolls into view".to_string())),
+                },
+                "video" if element.has_attr("poster") => {
+                    images += 1;
+                    if above_fold || element.has_attr("autoplay") {
+                        None
+                    } else {
+                        Some((Severity::Info, "loads its poster image and, unless preload=\"none\" is set, the video metadata immediately. the loading attribute does not apply to videos, set the poster and preload when the video scrolls into view".to_string()))
+                    }
+                }
+                _ => None,
+            };
+            if let Some((severity, description)) = finding {
+                result.push(element_result(
+                    self,
+                    document,
+                    element,
+                    severity,
+                    &description,
+                ));
+            }
+        }
+        result
+    }
+
+    /// Check a single image, images above the fold should not be lazy-loaded
+    fn check_image(
+        &self,
+        image: &Element,
+        above_fold: bool,
+        in_picture: bool,
+    ) -> Option<(Severity, String)> {
+        if is_lazy_loaded_by_script(image) {
+            return None;
+        }
+        match loading(image) {
+            Some("lazy") if above_fold => Some((Severity::Info, format!("is one of the first {} images and probably visible without scrolling. lazy-loading it delays the largest contentful paint, remove loading=\"lazy\"", self.above_fold_images))),
+            // `loading="eager"` is an explicit decision
+            Some("lazy" | "eager") => None,
+            _ if above_fold => None,
+            _ if in_picture => Some((Severity::Warning, "is loaded together with the page. add loading=\"lazy\" to the <img> inside <picture>, it applies to all of its sources".to_string())),
+            _ => Some((Severity::Warning, "is loaded together with the page. add loading=\"lazy\" so it is only loaded when it scrolls into view".to_string())),
+        }
+    }
 }
 
-fn lazy_loading_selector(fragment: &Html, html: &str, selector: &str) -> Vec<LineResult> {
-    let selector = Selector::parse(selector).unwrap();
-    let result = fragment
-        .select(&selector)
-        .filter(|element| element.value().attr("loading").is_none())
-        .map(|element| {
-            let line_column = get_line_and_column(html, &element);
-            LineResult {
-                severity: Severity::Warning,
-                line: line_column.0,
-                column: line_column.1,
-                classification: "Lazy-Loading".to_string(),
-                description: element.html(),
-            }
-        })
-        .collect();
-    result
+// region: helpers
+
+/// Get the lowercase value of the loading attribute
+fn loading(element: &Element) -> Option<&'static str> {
+    match element
+        .attr("loading")?
+        .trim()
+        .to_ascii_lowercase()
+        .as_str()
+    {
+        "lazy" => Some("lazy"),
+        "eager" => Some("eager"),
+        _ => None,
+    }
 }
 
-fn get_line_and_column(html: &str, element: &scraper::ElementRef) -> (i32, i32) {
-    let mut line_ctr = 0;
-    for line in html.lines() {
-        line_ctr += 1;
-        if line.contains(element.value().attr("src").unwrap()) {
-            return (
-                line_ctr,
-                line.find(element.value().attr("src").unwrap()).unwrap() as i32,
-            );
-        }
+/// Check if the image is already lazy-loaded by a script like lazysizes, which uses `data-src`
+fn is_lazy_loaded_by_script(image: &Element) -> bool {
+    image.has_attr("data-src")
+        || image.has_attr("data-srcset")
+        || image
+            .attr("class")
+            .is_some_and(|class| class.split_whitespace().any(|class| class == "lazyload"))
+}
+
+// endregion: helpers
+// region: tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::rules::html::apply_to_source;
+
+    #[test]
+    fn test_images_without_src_do_not_panic() {
+        let html = "<img srcset=\"a.png 1x, a@2x.png 2x\"><img alt=\"none\">
+<img data-src=\"lazy.png\" class=\"lazyload\"><img srcset=\"b.png 1x\">";
+        let result = apply_to_source(&Loading::default(), html);
+        assert_eq!(result.len(), 1);
+        assert_eq!((result[0].line, result[0].column), (2, 43));
+    }
+
+    #[test]
+    fn test_duplicated_urls_get_their_own_position() {
+        let html = "<iframe src=\"map.html\"></iframe>
+<p>text</p>
+<iframe src=\"map.html\" loading=\"eager\"></iframe>
+<iframe src=\"map.html\"></iframe>";
+        let result = apply_to_source(&Loading::default(), html);
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].line, 1);
+        assert_eq!(result[1].line, 4);
+    }
+
+    #[test]
+    fn test_above_fold_images_and_pictures() {
+        let html = "<img src=\"hero.jpg\" loading=\"lazy\">
+<picture><source srcset=\"team.avif\"><img src=\"team.jpg\"></picture>
+<img src=\"footer.png\" loading=\"eager\">
+<video src=\"clip.mp4\" poster=\"clip.jpg\"></video>";
+        let result = apply_to_source(&Loading::default(), html);
+        assert_eq!(result.len(), 2);
+        assert!(matches!(result[0].severity, Severity::Info));
+        assert!(result[0].description.contains("remove loading=\"lazy\""));
+        assert_eq!(result[1].line, 4);
+
+        let rule = Loading {
+            above_fold_images: 0,
+        };
+        let result = apply_to_source(&rule, html);
+        assert_eq!(result.len(), 2);
+        assert!(result[0].description.contains("inside <picture>"));
     }
-    (0, 0)
 }
+// endregion: tests
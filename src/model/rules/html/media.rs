@@ -0,0 +1,186 @@
+use crate::model::ctx::Ctx;
+use crate::model::rules::LineResult;
+use crate::model::rules::Severity;
+
+use crate::model::dom::Element;
+
+use super::{element_result, url_extension, Rule};
+
+/// This rule is used to find video and audio elements that download more data than is played
+pub struct MediaElements;
+
+impl Rule for MediaElements {
+    fn get_name(&self) -> &str {
+        "Media-Elements"
+    }
+
+    fn get_description(&self) -> &str {
+        "video and audio files are large, the browser should only download what is actually played."
+    }
+
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        if let Ctx::HtmlCtx(html_ctx) = ctx {
+            let document = html_ctx.document;
+            let mut result = vec![];
+            for element in &document.elements {
+                if element.name != "video" && element.name != "audio" {
+                    continue;
+                }
+                for (severity, message) in check_media(element) {
+                    result.push(element_result(self, document, element, severity, &message));
+                }
+            }
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+/// This rule is used to find GIF images, animated GIFs are much larger than the same clip as video
+pub struct AnimatedGif;
+
+impl Rule for AnimatedGif {
+    fn get_name(&self) -> &str {
+        "Animated-GIF"
+    }
+
+    fn get_description(&self) -> &str {
+        "video codecs compress animations much better than GIF, which stores every frame as a separate image."
+    }
+
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        if let Ctx::HtmlCtx(html_ctx) = ctx {
+            let document = html_ctx.document;
+            let mut result = vec![];
+            for image in document
+                .elements
+                .iter()
+                .filter(|element| element.name == "img")
+            {
+                let is_gif = image.attr("src").is_some_and(|src| {
+                    !src.trim_start().starts_with("data:")
+                        && url_extension(src).as_deref() == Some("gif")
+                });
+                if is_gif {
+                    result.push(element_result(
+                        self,
+                        document,
+                        image,
+                        Severity::Warning,
+                        "is a GIF. if it is animated, the same clip as MP4 or WebM is often 5 to 10 times smaller, use <video autoplay loop muted playsinline> instead",
+                    ));
+                }
+            }
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+// region: helpers
+
+/// Check the autoplay, preload and poster attributes of a video or audio element
+fn check_media(element: &Element) -> Vec<(Severity, String)> {
+    let mut findings = vec![];
+    if element.has_attr("autoplay") {
+        let mut message = format!(
+            "downloads and plays the whole {} when the page loads, even if nobody watches it. start it when it scrolls into view or let the user start it",
+            element.name
+        );
+        if !element.has_attr("muted") {
+            message.push_str(". browsers block autoplay with sound, so without muted it is downloaded but does not play");
+        }
+        findings.push((Severity::Warning, message));
+        // autoplay ignores preload and replaces the poster with the first frame
+        return findings;
+    }
+
+    let preload = element
+        .attr("preload")
+        .map(|preload| preload.trim().to_ascii_lowercase());
+    match preload.as_deref() {
+        Some("none" | "metadata") => {}
+        // an empty preload attribute means auto
+        Some("auto" | "") => findings.push((
+            Severity::Warning,
+            format!(
+                "asks the browser to buffer the whole {} before anyone presses play. use preload=\"none\" or preload=\"metadata\"",
+                element.name
+            ),
+        )),
+        _ => findings.push((
+            Severity::Info,
+            "has no preload attribute, so the browser decides how much to buffer, often several megabytes. add preload=\"none\" or preload=\"metadata\"".to_string(),
+        )),
+    }
+
+    if element.name == "video" && !element.has_attr("poster") && preload.as_deref() != Some("none")
+    {
+        findings.push((
+            Severity::Info,
+            "has no poster, so video data is downloaded just to show the first frame. add a poster image together with preload=\"none\"".to_string(),
+        ));
+    }
+    findings
+}
+
+// endregion: helpers
+// region: tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::rules::html::apply_to_source;
+    use crate::model::rules::html::images::ModernImageFormats;
+
+    #[test]
+    fn test_autoplaying_media() {
+        let html = "<video src=\"intro.mp4\" autoplay muted loop playsinline></video>
+<audio src=\"theme.mp3\" autoplay></audio>";
+        let result = apply_to_source(&MediaElements, html);
+        assert_eq!(result.len(), 2);
+        assert!(matches!(result[0].severity, Severity::Warning));
+        assert!(result[0]
+            .description
+            .contains("downloads and plays the whole video when the page loads"));
+        assert!(!result[0].description.contains("without muted"));
+        assert_eq!(result[1].line, 2);
+        assert!(result[1].description.contains("without muted"));
+    }
+
+    #[test]
+    fn test_preload_and_poster() {
+        let html = "<video src=\"a.mp4\" preload=\"none\" controls></video>
+<video src=\"b.mp4\" preload=\"metadata\" poster=\"b.jpg\" controls></video>
+<video src=\"c.mp4\" controls></video>
+<audio src=\"d.mp3\" preload=\"auto\" controls></audio>
+<audio src=\"e.mp3\" preload controls></audio>";
+        let result = apply_to_source(&MediaElements, html);
+        let lines: Vec<i32> = result.iter().map(|line_result| line_result.line).collect();
+        assert_eq!(lines, [3, 3, 4, 5]);
+        assert!(result[0].description.contains("has no preload attribute"));
+        assert!(result[1].description.contains("has no poster"));
+        assert!(result[2]
+            .description
+            .contains("buffer the whole audio before anyone presses play"));
+    }
+
+    #[test]
+    fn test_gif_images() {
+        let html = "<img src=\"/media/loading.GIF?v=3\" width=\"32\" height=\"32\">
+<img src=\"photo.jpg\">
+<img src=\"data:image/gif;base64,R0lGODlhAQABAAAAACw=\">";
+        let result = apply_to_source(&AnimatedGif, html);
+        assert_eq!(result.len(), 1);
+        assert_eq!((result[0].line, result[0].column), (1, 1));
+        assert!(result[0]
+            .description
+            .contains("<video autoplay loop muted playsinline>"));
+
+        // a GIF is only reported once, not also as a legacy image format
+        let formats = apply_to_source(&ModernImageFormats, html);
+        assert!(formats.iter().all(|line_result| line_result.line != 1));
+    }
+}
+// endregion: tests
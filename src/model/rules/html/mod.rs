@@ -1,2 +1,56 @@
-use super::Rule;
+use crate::model::dom::{Document, Element};
+
+use super::{LineResult, Rule, Severity};
+pub mod dom_size;
+pub mod embeds;
+pub mod images;
 pub mod loading;
+pub mod media;
+pub mod render_blocking;
+
+// region: helpers
+
+/// Create a result at the start tag of the element, the description quotes the start tag
+pub fn element_result(
+    rule: &dyn Rule,
+    document: &Document<'_>,
+    element: &Element,
+    severity: Severity,
+    message: &str,
+) -> LineResult {
+    let (line, column) = document.position(element.span.start);
+    LineResult {
+        severity,
+        line,
+        column,
+        classification: rule.get_name().to_string(),
+        description: format!(
+            "'{}' {}. {}",
+            document.source(element),
+            message,
+            rule.get_description()
+        ),
+    }
+}
+
+/// Get the lowercase file extension of the url without query and fragment
+pub fn url_extension(url: &str) -> Option<String> {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let file = path.rsplit('/').next().unwrap_or_default();
+    let (_, extension) = file.rsplit_once('.')?;
+    Some(extension.trim().to_ascii_lowercase())
+}
+
+// endregion: helpers
+
+/// Parse the html and apply the rule to it, used by the rule tests
+#[cfg(test)]
+pub fn apply_to_source(rule: &dyn Rule, input: &str) -> Vec<LineResult> {
+    use crate::model::ctx::{Ctx, HtmlCtx};
+
+    let document = Document::parse(input);
+    let ctx = Ctx::HtmlCtx(HtmlCtx {
+        document: &document,
+    });
+    rule.apply(&ctx).unwrap_or_default()
+}
@@ -0,0 +1,245 @@
+use crate::model::ctx::Ctx;
+use crate::model::rules::LineResult;
+use crate::model::rules::Severity;
+
+use crate::model::dom::{Document, Element};
+
+use super::{element_result, Rule};
+
+/// This rule is used to find scripts and stylesheets that block the first render of the page
+#[derive(Debug)]
+pub struct RenderBlocking {
+    /// more separate scripts or stylesheets than this should be bundled
+    pub max_requests: usize,
+}
+
+impl Default for RenderBlocking {
+    fn default() -> Self {
+        RenderBlocking { max_requests: 4 }
+    }
+}
+
+impl Rule for RenderBlocking {
+    fn get_name(&self) -> &str {
+        "Render-Blocking"
+    }
+
+    fn get_description(&self) -> &str {
+        "the browser cannot show the page until render-blocking scripts and stylesheets are downloaded and executed."
+    }
+
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        if let Ctx::HtmlCtx(html_ctx) = ctx {
+            let document = html_ctx.document;
+            let mut result = vec![];
+            let scripts: Vec<&Element> = document
+                .elements
+                .iter()
+                .filter(|element| is_external_script(element))
+                .collect();
+            let stylesheets: Vec<&Element> = document
+                .elements
+                .iter()
+                .filter(|element| is_stylesheet(element))
+                .collect();
+
+            for script in &scripts {
+                if in_head(document, script) && is_blocking_script(script) {
+                    result.push(element_result(
+                        self,
+                        document,
+                        script,
+                        Severity::Warning,
+                        "blocks parsing until it is downloaded and executed. add defer, or async if it does not depend on other scripts",
+                    ));
+                }
+            }
+
+            let blocking_stylesheets: Vec<&Element> = stylesheets
+                .iter()
+                .copied()
+                .filter(|stylesheet| {
+                    in_head(document, stylesheet) && is_blocking_stylesheet(stylesheet)
+                })
+                .collect();
+            if let [first, rest @ ..] = blocking_stylesheets.as_slice() {
+                if !rest.is_empty() {
+                    let lines: Vec<String> = rest
+                        .iter()
+                        .map(|stylesheet| document.position(stylesheet.span.start).0.to_string())
+                        .collect();
+                    let message = format!(
+                        "starts a chain of {} render-blocking stylesheets (also on line(s) {}). combine them or inline the critical css and load the rest with media=\"print\" onload=\"this.media='all'\"",
+                        blocking_stylesheets.len(),
+                        lines.join(", ")
+                    );
+                    result.push(element_result(
+                        self,
+                        document,
+                        first,
+                        Severity::Warning,
+                        &message,
+                    ));
+                }
+            }
+
+            for style in document
+                .elements
+                .iter()
+                .filter(|element| element.name == "style")
+            {
+                result.extend(self.find_imports(document, style));
+            }
+
+            for (requests, kind) in [(&scripts, "scripts"), (&stylesheets, "stylesheets")] {
+                if requests.len() > self.max_requests {
+                    let message = format!(
+                        "is the first of {} separate {}. every request adds a round trip, bundle them into fewer files",
+                        requests.len(),
+                        kind
+                    );
+                    result.push(element_result(
+                        self,
+                        document,
+                        requests[0],
+                        Severity::Info,
+                        &message,
+                    ));
+                }
+            }
+
+            result.sort_by_key(|line_result| (line_result.line, line_result.column));
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+impl RenderBlocking {
+    /// Find `@import` rules in a `<style>` element, the imported stylesheet is only discovered after the style is parsed
+    fn find_imports(&self, document: &Document<'_>, style: &Element) -> Vec<LineResult> {
+        let (Some(content), Some(range)) = (document.content(style), &style.content) else {
+            return vec![];
+        };
+        let mut result = vec![];
+        for (offset, _) in content.match_indices("@import") {
+            let statement = content[offset..]
+                .split([';', '\n'])
+                .next()
+                .unwrap_or_default()
+                .trim();
+            let (line, column) = document.position(range.start + offset);
+            result.push(LineResult {
+                severity: Severity::Warning,
+                line,
+                column,
+                classification: self.get_name().to_string(),
+                description: format!(
+                    "'{}' loads the stylesheet only after this style is parsed, which delays the render. use a <link rel=\"stylesheet\"> instead. {}",
+                    statement,
+                    self.get_description()
+                ),
+            });
+        }
+        result
+    }
+}
+
+// region: helpers
+
+fn in_head(document: &Document<'_>, element: &Element) -> bool {
+    document
+        .ancestors(element)
+        .any(|ancestor| ancestor.name == "head")
+}
+
+fn is_external_script(element: &Element) -> bool {
+    element.name == "script"
+        && element.has_attr("src")
+        && element.attr("type").is_none_or(|kind| {
+            let kind = kind.trim().to_ascii_lowercase();
+            kind.is_empty() || kind == "module" || kind.contains("javascript")
+        })
+}
+
+/// Check if the script stops the parser, modules are deferred and `nomodule` scripts are skipped by modern browsers
+fn is_blocking_script(script: &Element) -> bool {
+    !script.has_attr("defer")
+        && !script.has_attr("async")
+        && !script.has_attr("nomodule")
+        && script
+            .attr("type")
+            .is_none_or(|kind| !kind.trim().eq_ignore_ascii_case("module"))
+}
+
+fn is_stylesheet(element: &Element) -> bool {
+    element.name == "link"
+        && element.has_attr("href")
+        && element.attr("rel").is_some_and(|rel| {
+            let rel = rel.to_ascii_lowercase();
+            let mut values = rel.split_whitespace();
+            values.clone().any(|value| value == "stylesheet")
+                && !values.any(|value| value == "alternate")
+        })
+}
+
+/// Check if the stylesheet blocks the render, stylesheets for other media like print do not
+fn is_blocking_stylesheet(stylesheet: &Element) -> bool {
+    !stylesheet.has_attr("disabled")
+        && stylesheet.attr("media").is_none_or(|media| {
+            let media = media.trim().to_ascii_lowercase();
+            media.is_empty() || media == "all" || media == "screen"
+        })
+}
+
+// endregion: helpers
+// region: tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::rules::html::apply_to_source;
+
+    #[test]
+    fn test_stack_overflow_page() {
+        let html = include_str!("../../../../testfiles/img_iframe_html_test");
+        let result = apply_to_source(&RenderBlocking::default(), html);
+        let lines: Vec<i32> = result.iter().map(|line_result| line_result.line).collect();
+        // jquery and the stub are loaded synchronously, the stacks script is deferred
+        assert_eq!(lines, [23, 23, 25, 27]);
+        assert!(result[0].description.contains("blocks parsing"));
+        assert!(result[1].description.contains("separate scripts"));
+        assert!(result[3]
+            .description
+            .contains("chain of 3 render-blocking stylesheets (also on line(s) 28, 64)"));
+    }
+
+    #[test]
+    fn test_deferred_scripts_and_non_blocking_stylesheets_are_ignored() {
+        let html = "<html><head>
+<script type=\"module\" src=\"app.js\"></script>
+<script async src=\"analytics.js\"></script>
+<script type=\"application/ld+json\">{}</script>
+<link rel=\"stylesheet\" href=\"main.css\">
+<link rel=\"stylesheet\" href=\"print.css\" media=\"print\">
+<link rel=\"alternate stylesheet\" href=\"dark.css\">
+</head><body><script src=\"footer.js\"></script></body></html>";
+        let result = apply_to_source(&RenderBlocking::default(), html);
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_imports_in_style_elements() {
+        let html = "<head><style>
+  @import url(\"fonts.css\");
+  body { margin: 0 }
+</style></head>";
+        let result = apply_to_source(&RenderBlocking::default(), html);
+        assert_eq!(result.len(), 1);
+        assert_eq!((result[0].line, result[0].column), (2, 3));
+        assert!(result[0]
+            .description
+            .starts_with("'@import url(\"fonts.css\")' loads the stylesheet"));
+    }
+}
+// endregion: tests
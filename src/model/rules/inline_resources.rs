@@ -0,0 +1,353 @@
+use std::ops::Range;
+
+use crate::model::ctx::Ctx;
+use crate::model::dom::Document;
+use crate::model::rules::LineResult;
+use crate::model::rules::Severity;
+
+use super::{line_column, Rule};
+
+/// This rule is used to find large `data:` URIs in html and css
+#[derive(Debug)]
+pub struct DataUris {
+    /// data URIs with more decoded bytes than this are reported
+    pub max_bytes: usize,
+}
+
+impl Default for DataUris {
+    fn default() -> Self {
+        DataUris { max_bytes: 4096 }
+    }
+}
+
+impl Rule for DataUris {
+    fn get_name(&self) -> &str {
+        "Inline-Data-URIs"
+    }
+
+    fn get_description(&self) -> &str {
+        "a data URI is downloaded again with every page or stylesheet that contains it and base64 adds a third to its size. move it into a separate file that the browser can cache."
+    }
+
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        let (input, uris) = match ctx {
+            Ctx::HtmlCtx(html_ctx) => (
+                html_ctx.document.input,
+                document_data_uris(html_ctx.document),
+            ),
+            Ctx::CssCtx(css_ctx) => (css_ctx.input, data_uris(css_ctx.input)),
+            Ctx::JavaScriptCtx(_) => return None,
+        };
+        let mut result = vec![];
+        for (start, uri) in uris {
+            let decoded = decoded_size(uri);
+            if decoded <= self.max_bytes {
+                continue;
+            }
+            let header = uri.split(',').next().unwrap_or_default();
+            let (line, column) = line_column(input, start as u32);
+            result.push(LineResult {
+                severity: Severity::Warning,
+                line,
+                column,
+                classification: self.get_name().to_string(),
+                description: format!(
+                    "'{},...' is {} decoded and {} in the source. {}",
+                    header,
+                    kilobytes(decoded),
+                    kilobytes(uri.len()),
+                    self.get_description()
+                ),
+            });
+        }
+        Some(result)
+    }
+}
+
+/// This rule is used to find large inline `<script>` and `<style>` elements
+#[derive(Debug)]
+pub struct InlineBlocks {
+    /// inline scripts and styles with more bytes than this are reported
+    pub max_bytes: usize,
+}
+
+impl Default for InlineBlocks {
+    fn default() -> Self {
+        InlineBlocks {
+            max_bytes: 16 * 1024,
+        }
+    }
+}
+
+impl Rule for InlineBlocks {
+    fn get_name(&self) -> &str {
+        "Inline-Blocks"
+    }
+
+    fn get_description(&self) -> &str {
+        "inline scripts and styles are downloaded again with every page. move large ones into external files that the browser can cache."
+    }
+
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        if let Ctx::HtmlCtx(html_ctx) = ctx {
+            let document = html_ctx.document;
+            let mut result = vec![];
+            for element in &document.elements {
+                if (element.name != "script" && element.name != "style") || element.has_attr("src")
+                {
+                    continue;
+                }
+                let Some(content) = document.content(element) else {
+                    continue;
+                };
+                let size = content.trim().len();
+                if size <= self.max_bytes {
+                    continue;
+                }
+                let (line, column) = document.position(element.span.start);
+                result.push(LineResult {
+                    severity: Severity::Warning,
+                    line,
+                    column,
+                    classification: self.get_name().to_string(),
+                    description: format!(
+                        "inline <{}> is {}. {}",
+                        element.name,
+                        kilobytes(size),
+                        self.get_description()
+                    ),
+                });
+            }
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+/// This rule is used to find identical inline `<svg>` elements that are repeated many times
+#[derive(Debug)]
+pub struct RepeatedSvgs {
+    /// inline svgs that occur more often than this are reported
+    pub max_repeats: usize,
+}
+
+impl Default for RepeatedSvgs {
+    fn default() -> Self {
+        RepeatedSvgs { max_repeats: 3 }
+    }
+}
+
+impl Rule for RepeatedSvgs {
+    fn get_name(&self) -> &str {
+        "Repeated-SVGs"
+    }
+
+    fn get_description(&self) -> &str {
+        "every copy of an inline svg adds its bytes to the page and its elements to the DOM. put the icon into an external sprite and reference it with <use href=\"icons.svg#name\"> or an <img>, which is downloaded once and cached."
+    }
+
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        if let Ctx::HtmlCtx(html_ctx) = ctx {
+            let document = html_ctx.document;
+            let mut result = vec![];
+            for (svg, offsets) in repeated_svgs(document) {
+                if offsets.len() <= self.max_repeats {
+                    continue;
+                }
+                let lines: Vec<String> = offsets[1..]
+                    .iter()
+                    .map(|offset| document.position(*offset).0.to_string())
+                    .collect();
+                let (line, column) = document.position(offsets[0]);
+                result.push(LineResult {
+                    severity: Severity::Warning,
+                    line,
+                    column,
+                    classification: self.get_name().to_string(),
+                    description: format!(
+                        "this inline <svg> of {} is repeated {} times (also on line(s) {}), {} in total. {}",
+                        kilobytes(svg.len()),
+                        offsets.len(),
+                        lines.join(", "),
+                        kilobytes(svg.len() * offsets.len()),
+                        self.get_description()
+                    ),
+                });
+            }
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+// region: helpers
+
+/// Find the data URIs in attribute values and `url()` functions with their byte offsets
+fn data_uris(input: &str) -> Vec<(usize, &str)> {
+    let bytes = input.as_bytes();
+    let mut uris = vec![];
+    for (start, _) in input.match_indices("data:") {
+        // a data URI starts an attribute value or a url, `data:` in text or code is ignored
+        let Some(&before) = start.checked_sub(1).and_then(|index| bytes.get(index)) else {
+            continue;
+        };
+        let rest = &input[start..];
+        let end = match before {
+            b'"' | b'\'' => rest.find(before as char),
+            b'(' => rest.find([')', '"', '\'']),
+            b'=' => rest.find(|c: char| c.is_ascii_whitespace() || c == '>'),
+            _ => continue,
+        }
+        .unwrap_or(rest.len());
+        let uri = &rest[..end];
+        // `data:image/png;base64,...`, the media type may be empty
+        if uri.contains(',') {
+            uris.push((start, uri));
+        }
+    }
+    uris
+}
+
+/// Find the data URIs in the attribute values and `<style>` elements of the document, strings in scripts are ignored
+fn document_data_uris<'a>(document: &Document<'a>) -> Vec<(usize, &'a str)> {
+    let mut uris = vec![];
+    for element in &document.elements {
+        let mut spans: Vec<Range<usize>> = element
+            .attributes
+            .iter()
+            .map(|attribute| attribute.span.clone())
+            .collect();
+        if element.name == "style" {
+            spans.extend(element.content.clone());
+        }
+        for span in spans {
+            let offset = span.start;
+            let Some(source) = document.input.get(span) else {
+                continue;
+            };
+            uris.extend(
+                data_uris(source)
+                    .into_iter()
+                    .map(|(start, uri)| (offset + start, uri)),
+            );
+        }
+    }
+    uris
+}
+
+/// Get the number of bytes the data URI decodes to
+fn decoded_size(uri: &str) -> usize {
+    let (header, data) = uri.split_once(',').unwrap_or((uri, ""));
+    if header.to_ascii_lowercase().ends_with(";base64") {
+        let data: Vec<u8> = data
+            .bytes()
+            .filter(|byte| !byte.is_ascii_whitespace())
+            .collect();
+        let padding = data.iter().rev().take_while(|byte| **byte == b'=').count();
+        (data.len() * 3 / 4).saturating_sub(padding)
+    } else {
+        // every percent escape like `%3C` is a single byte
+        data.len().saturating_sub(2 * data.matches('%').count())
+    }
+}
+
+/// Group the top-level inline svgs by their source, the svgs are in order of their first occurrence
+fn repeated_svgs<'a>(document: &Document<'a>) -> Vec<(&'a str, Vec<usize>)> {
+    let mut svgs: Vec<(&'a str, Vec<usize>)> = vec![];
+    for svg in &document.elements {
+        if svg.name != "svg"
+            || document
+                .ancestors(svg)
+                .any(|ancestor| ancestor.name == "svg")
+        {
+            continue;
+        }
+        let Some(source) = document.input.get(svg.span.start..svg.end) else {
+            continue;
+        };
+        match svgs.iter_mut().find(|(other, _)| *other == source) {
+            Some((_, offsets)) => offsets.push(svg.span.start),
+            None => svgs.push((source, vec![svg.span.start])),
+        }
+    }
+    svgs
+}
+
+/// Format a byte size like `12.3 KB`
+fn kilobytes(bytes: usize) -> String {
+    format!("{:.1} KB", bytes as f64 / 1024.0)
+}
+
+// endregion: helpers
+// region: tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ctx::CssCtx;
+    use crate::model::rules::html::apply_to_source;
+
+    #[test]
+    fn test_large_data_uris_in_html_and_css() {
+        let image = "A".repeat(8000);
+        let html = format!(
+            "<p>read data: from the api</p>\n<img src=\"data:image/png;base64,{image}\" alt=\"\">\n<img src=data:image/gif;base64,R0lGODlhAQABAAAAACw=>\n<script>const placeholder = 'data:image/png;base64,{image}';</script>\n<style>.hero {{ background: url(data:image/png;base64,{image}) }}</style>"
+        );
+        let result = apply_to_source(&DataUris::default(), &html);
+        assert_eq!(result.len(), 2);
+        assert_eq!((result[0].line, result[0].column), (2, 11));
+        assert_eq!((result[1].line, result[1].column), (5, 32));
+        assert!(result[0]
+            .description
+            .starts_with("'data:image/png;base64,...' is 5.9 KB decoded and 7.8 KB in the source"));
+
+        let css = format!(
+            ".logo {{ background: url(data:image/svg+xml,{}) }}\n.font {{ src: url('data:font/woff2;base64,{}') }}",
+            "%3Csvg%3E".repeat(400),
+            image
+        );
+        let result = DataUris::default()
+            .apply(&Ctx::CssCtx(CssCtx { input: &css }))
+            .unwrap_or_default();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].line, 2);
+    }
+
+    #[test]
+    fn test_large_inline_scripts_and_styles() {
+        let html = format!(
+            "<script>{}</script>\n<style>{}</style>\n<script src=\"app.js\"></script><script>var small = 1;</script>",
+            "console.log(1);".repeat(2000),
+            "a{}".repeat(100)
+        );
+        let result = apply_to_source(&InlineBlocks::default(), &html);
+        assert_eq!(result.len(), 1);
+        assert!(result[0]
+            .description
+            .starts_with("inline <script> is 29.3 KB"));
+
+        let rule = InlineBlocks { max_bytes: 100 };
+        let result = apply_to_source(&rule, &html);
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[1].line, 2);
+    }
+
+    #[test]
+    fn test_repeated_inline_svgs() {
+        let icon = "<svg viewBox=\"0 0 24 24\"><path d=\"M12 2L2 22h20z\"/></svg>";
+        let html = format!(
+            "<ul>\n<li>{icon} one\n<li>{icon} two\n<li>{icon} three\n<li>{icon} four\n</ul>\n<svg><circle r=\"1\"/></svg>"
+        );
+        let result = apply_to_source(&RepeatedSvgs::default(), &html);
+        assert_eq!(result.len(), 1);
+        assert_eq!((result[0].line, result[0].column), (2, 5));
+        assert!(result[0]
+            .description
+            .starts_with("this inline <svg> of 0.1 KB is repeated 4 times (also on line(s) 3, 4, 5), 0.2 KB in total"));
+
+        let rule = RepeatedSvgs { max_repeats: 4 };
+        assert!(apply_to_source(&rule, &html).is_empty());
+    }
+}
+// endregion: tests
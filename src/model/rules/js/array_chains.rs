@@ -0,0 +1,279 @@
+use std::collections::HashSet;
+
+use crate::model::ctx::Ctx;
+use crate::model::rules::LineResult;
+use crate::model::rules::Severity;
+
+use oxc::ast::ast::{BinaryExpression, CallExpression, ComputedMemberExpression, Expression};
+use oxc::ast::visit::walk::{
+    walk_binary_expression, walk_call_expression, walk_computed_member_expression,
+};
+use oxc::ast::Visit;
+use oxc::span::Span;
+use oxc::syntax::operator::BinaryOperator;
+
+use super::{line_column, numeric_value, Rule};
+
+/// array methods that can be part of a chain
+const CHAIN_METHODS: [&str; 10] = [
+    "map",
+    "filter",
+    "flatMap",
+    "reduce",
+    "forEach",
+    "some",
+    "every",
+    "find",
+    "findIndex",
+    "join",
+];
+
+/// array methods that allocate a new array, when another method is called on it the array is thrown away
+const ALLOCATING_METHODS: [&str; 3] = ["map", "filter", "flatMap"];
+
+/// This rule is used to find array method chains that iterate several times or allocate throwaway arrays
+pub struct ArrayChains;
+
+impl Rule for ArrayChains {
+    fn get_name(&self) -> &str {
+        "JS-Array-Chains"
+    }
+    fn get_description(&self) -> &str {
+        "every step of an array method chain iterates the whole array and allocates a new one"
+    }
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        if let Ctx::JavaScriptCtx(js_ctx) = ctx {
+            let mut finder = ChainFinder {
+                matches: vec![],
+                chain_members: HashSet::new(),
+            };
+            finder.visit_program(js_ctx.program);
+            finder.matches.sort_by_key(|(_, _, span)| span.start);
+
+            let mut result = vec![];
+            for (severity, description, span) in finder.matches {
+                let (line, column) = line_column(js_ctx.input, span.start);
+                result.push(LineResult {
+                    severity,
+                    line,
+                    column,
+                    classification: self.get_name().to_string(),
+                    description: format!(
+                        "{} (bytes {}..{}). {}",
+                        description,
+                        span.start,
+                        span.end,
+                        self.get_description()
+                    ),
+                });
+            }
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+/// Visitor that finds wasteful array method chains
+struct ChainFinder {
+    /// severity, description, span of the whole chain
+    matches: Vec<(Severity, String, Span)>,
+    /// inner calls of chains that were already reported as part of a longer chain
+    chain_members: HashSet<Span>,
+}
+
+impl<'a> Visit<'a> for ChainFinder {
+    fn visit_call_expression(&mut self, expr: &CallExpression<'a>) {
+        // `items.map(f).filter(g).map(h)`
+        if !self.chain_members.contains(&expr.span) {
+            let chain = chain_calls(expr);
+            let intermediates = chain
+                .iter()
+                .skip(1)
+                .filter(|(method, _)| ALLOCATING_METHODS.contains(method))
+                .count();
+            if intermediates >= 1 {
+                self.chain_members
+                    .extend(chain.iter().skip(1).map(|(_, span)| *span));
+                let methods: Vec<String> = chain
+                    .iter()
+                    .rev()
+                    .map(|(method, _)| format!("{}()", method))
+                    .collect();
+                let arrays = if intermediates == 1 {
+                    "an intermediate array".to_string()
+                } else {
+                    format!("{} intermediate arrays", intermediates)
+                };
+                self.matches.push((
+                    Severity::Info,
+                    format!("'{}' iterates the array {} times and allocates {}. combine the steps in a single 'reduce' or a 'for...of' loop", methods.join("."), chain.len(), arrays),
+                    expr.span,
+                ));
+            }
+        }
+        walk_call_expression(self, expr);
+    }
+
+    fn visit_binary_expression(&mut self, expr: &BinaryExpression<'a>) {
+        // `items.filter(f).length > 0` or `0 === items.filter(f).length`
+        let check = match (
+            filter_length(&expr.left),
+            filter_length(&expr.right),
+            numeric_value(&expr.left),
+            numeric_value(&expr.right),
+        ) {
+            (true, _, _, Some(value)) => existence_check(expr.operator, value),
+            (_, true, Some(value), _) => {
+                flipped(expr.operator).and_then(|operator| existence_check(operator, value))
+            }
+            _ => None,
+        };
+        match check {
+            Some(true) => self.matches.push((
+                Severity::Warning,
+                "'filter(...).length' only checks whether any element matches but builds the whole filtered array. use 'some(...)', which stops at the first match".to_string(),
+                expr.span,
+            )),
+            Some(false) => self.matches.push((
+                Severity::Warning,
+                "'filter(...).length' only checks whether no element matches but builds the whole filtered array. use '!some(...)' or 'every(...)', which stop early".to_string(),
+                expr.span,
+            )),
+            None => {}
+        }
+        walk_binary_expression(self, expr);
+    }
+
+    fn visit_computed_member_expression(&mut self, expr: &ComputedMemberExpression<'a>) {
+        // `items.filter(f)[0]`
+        if is_filter_call(&expr.object) && numeric_value(&expr.expression) == Some(0.0) {
+            self.matches.push((
+                Severity::Warning,
+                "'filter(...)[0]' builds the whole filtered array to take its first element. use 'find(...)', which stops at the first match".to_string(),
+                expr.span,
+            ));
+        }
+        walk_computed_member_expression(self, expr);
+    }
+}
+
+// region: helpers
+
+/// Get the method name and span of every call in the chain, starting with the outermost call
+/// the chain ends at the first call that is not an array method
+fn chain_calls<'c>(expr: &'c CallExpression<'_>) -> Vec<(&'c str, Span)> {
+    let mut chain = vec![];
+    let mut call = expr;
+    while let Some(member) = call.callee.get_member_expr() {
+        match member.static_property_name() {
+            Some(method) if CHAIN_METHODS.contains(&method) => chain.push((method, call.span)),
+            _ => break,
+        }
+        match member.object().get_inner_expression() {
+            Expression::CallExpression(inner) => call = inner,
+            _ => break,
+        }
+    }
+    chain
+}
+
+/// Check if the expression is a `filter(...)` method call
+fn is_filter_call(expr: &Expression<'_>) -> bool {
+    matches!(expr.get_inner_expression(), Expression::CallExpression(call)
+        if call.callee_name() == Some("filter") && call.callee.get_member_expr().is_some())
+}
+
+/// Check if the expression is `filter(...).length`
+fn filter_length(expr: &Expression<'_>) -> bool {
+    match expr.get_inner_expression().get_member_expr() {
+        Some(member) => {
+            member.static_property_name() == Some("length") && is_filter_call(member.object())
+        }
+        None => false,
+    }
+}
+
+/// Get the operator with swapped operands, `0 < length` is `length > 0`
+fn flipped(operator: BinaryOperator) -> Option<BinaryOperator> {
+    match operator {
+        BinaryOperator::LessThan => Some(BinaryOperator::GreaterThan),
+        BinaryOperator::LessEqualThan => Some(BinaryOperator::GreaterEqualThan),
+        BinaryOperator::GreaterThan => Some(BinaryOperator::LessThan),
+        BinaryOperator::GreaterEqualThan => Some(BinaryOperator::LessEqualThan),
+        BinaryOperator::Equality
+        | BinaryOperator::StrictEquality
+        | BinaryOperator::Inequality
+        | BinaryOperator::StrictInequality => Some(operator),
+        _ => None,
+    }
+}
+
+/// Check if `length <operator> value` tests for any match (`Some(true)`) or for no match (`Some(false)`)
+fn existence_check(operator: BinaryOperator, value: f64) -> Option<bool> {
+    match (operator, value) {
+        (BinaryOperator::GreaterThan, 0.0)
+        | (BinaryOperator::GreaterEqualThan, 1.0)
+        | (BinaryOperator::Inequality | BinaryOperator::StrictInequality, 0.0) => Some(true),
+        (BinaryOperator::LessThan, 1.0)
+        | (BinaryOperator::LessEqualThan, 0.0)
+        | (BinaryOperator::Equality | BinaryOperator::StrictEquality, 0.0) => Some(false),
+        _ => None,
+    }
+}
+
+// endregion: helpers
+// region: tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::rules::js::apply_to_source;
+
+    #[test]
+    fn test_long_chain_is_reported_once_with_its_span() {
+        let source_text =
+            "const names = users.map(toUser).filter(isActive).map((user) => user.name);
+            const total = users.map(toUser).length;";
+        let result = apply_to_source(&ArrayChains, source_text);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].column, 15);
+        assert!(result[0].description.starts_with(
+            "'map().filter().map()' iterates the array 3 times and allocates 2 intermediate arrays"
+        ));
+        assert!(result[0].description.contains("(bytes 14..73)"));
+    }
+
+    #[test]
+    fn test_filter_then_map_allocates_a_throwaway_array() {
+        let source_text = "const names = users.filter(isActive).map(toName);
+            const found = users.map(toUser).find(isAdmin);
+            const mapped = users.map(toUser);";
+        let result = apply_to_source(&ArrayChains, source_text);
+        assert_eq!(result.len(), 2);
+        assert!(result[0].description.starts_with(
+            "'filter().map()' iterates the array 2 times and allocates an intermediate array"
+        ));
+        assert_eq!(result[1].line, 2);
+    }
+
+    #[test]
+    fn test_filter_length_checks_suggest_some() {
+        let source_text = "if (items.filter(isBroken).length > 0) warn();
+            if (0 === items.filter(isBroken).length) ok();
+            const count = items.filter(isBroken).length;";
+        let result = apply_to_source(&ArrayChains, source_text);
+        assert_eq!(result.len(), 2);
+        assert!(result[0].description.contains("use 'some(...)'"));
+        assert!(result[1].description.contains("use '!some(...)'"));
+    }
+
+    #[test]
+    fn test_filter_first_element_suggests_find() {
+        let source_text = "const admin = users.filter((user) => user.isAdmin)[0];
+            const second = users.filter((user) => user.isAdmin)[1];";
+        let result = apply_to_source(&ArrayChains, source_text);
+        assert_eq!(result.len(), 1);
+        assert!(result[0].description.contains("use 'find(...)'"));
+    }
+}
+// endregion: tests
@@ -0,0 +1,331 @@
+use std::collections::HashSet;
+
+use crate::model::ctx::Ctx;
+use crate::model::rules::LineResult;
+use crate::model::rules::Severity;
+
+use oxc::ast::ast::{
+    Argument, ArrowFunctionExpression, AssignmentExpression, CallExpression, Expression, Function,
+    NewExpression, VariableDeclarator,
+};
+use oxc::ast::visit::walk::{
+    walk_arrow_function_expression, walk_assignment_expression, walk_call_expression,
+    walk_function, walk_new_expression, walk_variable_declarator,
+};
+use oxc::ast::Visit;
+use oxc::semantic::{Semantic, SymbolId};
+use oxc::syntax::scope::ScopeFlags;
+
+use super::{line_column, resolve_symbol, Rule};
+
+/// events that fire while the page is loading
+const LOAD_EVENTS: [&str; 2] = ["DOMContentLoaded", "load"];
+
+/// media elements that download and decode audio or video
+const MEDIA_TAGS: [&str; 2] = ["audio", "video"];
+
+/// This rule is used to find media that is played or created while the page loads
+pub struct AutoplayMedia;
+
+impl Rule for AutoplayMedia {
+    fn get_name(&self) -> &str {
+        "JS-Autoplay-Media"
+    }
+    fn get_description(&self) -> &str {
+        "media started or created while the page loads is downloaded and decoded even if the user never watches it. wait for a user interaction like a click before loading or playing it"
+    }
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        if let Ctx::JavaScriptCtx(js_ctx) = ctx {
+            // named functions passed as load handlers run on load as well
+            let mut collector = LoadHandlerCollector {
+                semantic: js_ctx.semantic,
+                handlers: HashSet::new(),
+            };
+            collector.visit_program(js_ctx.program);
+
+            let mut finder = MediaFinder {
+                load_handlers: collector.handlers,
+                matches: vec![],
+                on_load: true,
+                saved_on_load: vec![],
+                load_callback: false,
+            };
+            finder.visit_program(js_ctx.program);
+
+            let mut result = vec![];
+            for (severity, description, start) in finder.matches {
+                let (line, column) = line_column(js_ctx.input, start);
+                result.push(LineResult {
+                    severity,
+                    line,
+                    column,
+                    classification: self.get_name().to_string(),
+                    description: format!("{}. {}", description, self.get_description()),
+                });
+            }
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+/// Visitor that collects the functions registered as `load` or `DOMContentLoaded` handlers by name
+struct LoadHandlerCollector<'s, 'a> {
+    semantic: &'s Semantic<'a>,
+    handlers: HashSet<SymbolId>,
+}
+
+impl<'a> Visit<'a> for LoadHandlerCollector<'_, 'a> {
+    fn visit_call_expression(&mut self, expr: &CallExpression<'a>) {
+        // `window.addEventListener('load', init)`
+        if is_load_listener(expr) {
+            if let Some(Expression::Identifier(handler)) =
+                expr.arguments.get(1).and_then(Argument::as_expression)
+            {
+                self.handlers.extend(resolve_symbol(self.semantic, handler));
+            }
+        }
+        walk_call_expression(self, expr);
+    }
+
+    fn visit_assignment_expression(&mut self, expr: &AssignmentExpression<'a>) {
+        // `window.onload = init`
+        if is_onload_assignment(expr) {
+            if let Expression::Identifier(handler) = &expr.right {
+                self.handlers.extend(resolve_symbol(self.semantic, handler));
+            }
+        }
+        walk_assignment_expression(self, expr);
+    }
+}
+
+/// Visitor that finds media playback and creation in code that runs while the page loads
+struct MediaFinder {
+    load_handlers: HashSet<SymbolId>,
+    /// severity, description, start
+    matches: Vec<(Severity, String, u32)>,
+    /// whether the current code runs on load: top level code and load handlers
+    on_load: bool,
+    /// on load state of the enclosing functions
+    saved_on_load: Vec<bool>,
+    /// set while visiting the handler of a load listener
+    load_callback: bool,
+}
+
+impl<'a> Visit<'a> for MediaFinder {
+    fn visit_call_expression(&mut self, expr: &CallExpression<'a>) {
+        if self.on_load {
+            if let Some(description) = media_call(expr) {
+                self.matches
+                    .push((Severity::Warning, description, expr.span.start));
+            }
+        }
+        if is_load_listener(expr) {
+            self.visit_expression(&expr.callee);
+            self.load_callback = true;
+            self.visit_arguments(&expr.arguments);
+            self.load_callback = false;
+        } else {
+            walk_call_expression(self, expr);
+        }
+    }
+
+    fn visit_new_expression(&mut self, expr: &NewExpression<'a>) {
+        // `new Audio('/sounds/intro.mp3')`
+        if self.on_load && expr.callee.is_specific_id("Audio") {
+            self.matches.push((
+                Severity::Info,
+                "'new Audio()' creates and buffers the audio while the page loads".to_string(),
+                expr.span.start,
+            ));
+        }
+        walk_new_expression(self, expr);
+    }
+
+    fn visit_assignment_expression(&mut self, expr: &AssignmentExpression<'a>) {
+        // `video.autoplay = true`
+        if self.on_load {
+            let sets_autoplay = expr
+                .left
+                .as_member_expression()
+                .is_some_and(|member| member.static_property_name() == Some("autoplay"))
+                && expr.right.get_boolean_value() == Some(true);
+            if sets_autoplay {
+                self.matches.push((
+                    Severity::Warning,
+                    "'autoplay = true' starts downloading and playing the media as soon as possible"
+                        .to_string(),
+                    expr.span.start,
+                ));
+            }
+        }
+        if is_onload_assignment(expr) {
+            self.visit_assignment_target(&expr.left);
+            self.load_callback = true;
+            self.visit_expression(&expr.right);
+            self.load_callback = false;
+        } else {
+            walk_assignment_expression(self, expr);
+        }
+    }
+
+    fn visit_function(&mut self, func: &Function<'a>, flags: ScopeFlags) {
+        let is_load_handler = func
+            .id
+            .as_ref()
+            .and_then(|id| id.symbol_id.get())
+            .is_some_and(|symbol_id| self.load_handlers.contains(&symbol_id));
+        self.enter_function(is_load_handler);
+        walk_function(self, func, flags);
+        self.leave_function();
+    }
+
+    fn visit_arrow_function_expression(&mut self, expr: &ArrowFunctionExpression<'a>) {
+        self.enter_function(false);
+        walk_arrow_function_expression(self, expr);
+        self.leave_function();
+    }
+
+    fn visit_variable_declarator(&mut self, declarator: &VariableDeclarator<'a>) {
+        // `const init = () => { ... }` registered with `addEventListener('load', init)`
+        let is_load_handler = declarator
+            .id
+            .get_binding_identifier()
+            .and_then(|id| id.symbol_id.get())
+            .is_some_and(|symbol_id| self.load_handlers.contains(&symbol_id));
+        if is_load_handler
+            && matches!(
+                declarator.init,
+                Some(Expression::ArrowFunctionExpression(_) | Expression::FunctionExpression(_))
+            )
+        {
+            self.load_callback = true;
+        }
+        walk_variable_declarator(self, declarator);
+        self.load_callback = false;
+    }
+}
+
+impl MediaFinder {
+    /// A function body does not run on load, unless it is a load handler
+    fn enter_function(&mut self, is_load_handler: bool) {
+        self.saved_on_load.push(self.on_load);
+        self.on_load = is_load_handler || std::mem::take(&mut self.load_callback);
+    }
+
+    fn leave_function(&mut self) {
+        self.on_load = self.saved_on_load.pop().unwrap_or_default();
+    }
+}
+
+// region: helpers
+
+/// Describe the call if it plays or creates media
+fn media_call(expr: &CallExpression<'_>) -> Option<String> {
+    let member = expr.callee.get_member_expr()?;
+    match member.static_property_name()? {
+        // `video.play()`
+        "play" if expr.arguments.is_empty() => {
+            Some("'play()' starts downloading and decoding the media while the page loads".to_string())
+        }
+        // `document.createElement('video')`
+        "createElement" if member.object().is_specific_id("document") => {
+            match expr.arguments.first()? {
+                Argument::StringLiteral(tag) if MEDIA_TAGS.contains(&tag.value.as_str()) => {
+                    Some(format!("'createElement(\"{}\")' creates a media element while the page loads", tag.value))
+                }
+                _ => None,
+            }
+        }
+        // `video.setAttribute('autoplay', '')`
+        "setAttribute" => match expr.arguments.first()? {
+            Argument::StringLiteral(name) if name.value == "autoplay" => Some(
+                "'setAttribute(\"autoplay\")' starts downloading and playing the media as soon as possible"
+                    .to_string(),
+            ),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Check if the call registers a `load` or `DOMContentLoaded` listener on the page
+fn is_load_listener(expr: &CallExpression<'_>) -> bool {
+    expr.callee.as_member_expression().is_some_and(|member| {
+        member.static_property_name() == Some("addEventListener") && is_page(member.object())
+    }) && matches!(expr.arguments.first(), Some(Argument::StringLiteral(event)) if LOAD_EVENTS.contains(&event.value.as_str()))
+}
+
+/// Check if the assignment sets `window.onload` or `document.body.onload`
+fn is_onload_assignment(expr: &AssignmentExpression<'_>) -> bool {
+    expr.left.as_member_expression().is_some_and(|member| {
+        member.static_property_name() == Some("onload") && is_page(member.object())
+    })
+}
+
+/// Check if the expression is `window`, `document` or `document.body`, `img.onload` only fires for the image
+fn is_page(expr: &Expression<'_>) -> bool {
+    expr.is_specific_id("window")
+        || expr.is_specific_id("document")
+        || expr.is_specific_member_access("document", "body")
+}
+
+// endregion: helpers
+// region: tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::rules::js::apply_to_source;
+
+    #[test]
+    fn test_top_level_media_is_reported() {
+        let source_text = "const intro = new Audio('/sounds/intro.mp3');
+            const hero = document.querySelector('video');
+            hero.autoplay = true;
+            hero.play();
+            button.addEventListener('click', () => { hero.play(); new Audio('/click.mp3'); });";
+        let result = apply_to_source(&AutoplayMedia, source_text);
+        assert_eq!(result.len(), 3);
+        assert!(matches!(result[0].severity, Severity::Info));
+        assert_eq!(result[1].line, 3);
+        assert_eq!(result[2].line, 4);
+    }
+
+    #[test]
+    fn test_load_handlers_are_reported() {
+        let source_text = "document.addEventListener('DOMContentLoaded', () => {
+                const video = document.createElement('video');
+                video.setAttribute('autoplay', '');
+            });
+            window.onload = function () { player.play(); };";
+        let result = apply_to_source(&AutoplayMedia, source_text);
+        assert_eq!(result.len(), 3);
+        assert!(result[0]
+            .description
+            .starts_with("'createElement(\"video\")'"));
+        assert_eq!(result[2].line, 5);
+    }
+
+    #[test]
+    fn test_named_load_handlers_are_resolved() {
+        let source_text = "function init() { backgroundVideo.play(); }
+            function onClick() { backgroundVideo.play(); }
+            window.addEventListener('load', init);
+            playButton.addEventListener('click', onClick);";
+        let result = apply_to_source(&AutoplayMedia, source_text);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].line, 1);
+    }
+
+    #[test]
+    fn test_image_load_handlers_are_ignored() {
+        let source_text = "img.onload = () => video.play();
+            img.addEventListener('load', () => audio.play());
+            document.body.onload = () => video.play();";
+        let result = apply_to_source(&AutoplayMedia, source_text);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].line, 3);
+    }
+}
+// endregion: tests
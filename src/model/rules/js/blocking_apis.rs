@@ -0,0 +1,291 @@
+use crate::model::ctx::Ctx;
+use crate::model::rules::LineResult;
+use crate::model::rules::Severity;
+
+use oxc::ast::ast::{
+    Argument, ArrowFunctionExpression, CallExpression, DoWhileStatement, Expression,
+    ForInStatement, ForOfStatement, ForStatement, Function, WhileStatement,
+};
+use oxc::ast::visit::walk::{
+    walk_arrow_function_expression, walk_call_expression, walk_do_while_statement,
+    walk_for_in_statement, walk_for_of_statement, walk_for_statement, walk_function,
+    walk_while_statement,
+};
+use oxc::ast::{AstKind, Visit};
+use oxc::semantic::{Semantic, SymbolId};
+use oxc::syntax::scope::ScopeFlags;
+
+use super::{line_column, resolve_symbol, Rule};
+
+/// synchronous Web Storage methods
+const STORAGE_METHODS: [&str; 5] = ["getItem", "setItem", "removeItem", "key", "clear"];
+
+/// modal dialogs that block the main thread until the user closes them
+const BLOCKING_DIALOGS: [&str; 3] = ["alert", "confirm", "prompt"];
+
+/// This rule is used to find synchronous XHR and other APIs that block the main thread
+pub struct BlockingApis;
+
+impl Rule for BlockingApis {
+    fn get_name(&self) -> &str {
+        "JS-Blocking-APIs"
+    }
+    fn get_description(&self) -> &str {
+        "synchronous APIs block the main thread, the page stops responding while the CPU keeps working"
+    }
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        if let Ctx::JavaScriptCtx(js_ctx) = ctx {
+            let mut finder = BlockingApiFinder {
+                semantic: js_ctx.semantic,
+                matches: vec![],
+                loop_depth: 0,
+                saved_loop_depths: vec![],
+            };
+            finder.visit_program(js_ctx.program);
+
+            let mut result = vec![];
+            for (description, start, _end) in &finder.matches {
+                let (line, column) = line_column(js_ctx.input, *start);
+                result.push(LineResult {
+                    severity: Severity::Warning,
+                    line,
+                    column,
+                    classification: self.get_name().to_string(),
+                    description: description.to_string(),
+                });
+            }
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+/// Visitor that finds blocking API calls, using the semantic model to resolve the called objects
+struct BlockingApiFinder<'s, 'a> {
+    semantic: &'s Semantic<'a>,
+    /// description, start, end
+    matches: Vec<(String, u32, u32)>,
+    /// number of loops around the current node within the current function
+    loop_depth: usize,
+    /// loop depths of the enclosing functions
+    saved_loop_depths: Vec<usize>,
+}
+
+impl<'a> Visit<'a> for BlockingApiFinder<'_, 'a> {
+    fn visit_call_expression(&mut self, expr: &CallExpression<'a>) {
+        if let Some(description) = self.blocking_call(expr) {
+            self.matches
+                .push((description, expr.span.start, expr.span.end));
+        }
+        walk_call_expression(self, expr);
+    }
+
+    fn visit_function(&mut self, func: &Function<'a>, flags: ScopeFlags) {
+        self.saved_loop_depths.push(self.loop_depth);
+        self.loop_depth = 0;
+        walk_function(self, func, flags);
+        self.loop_depth = self.saved_loop_depths.pop().unwrap_or_default();
+    }
+
+    fn visit_arrow_function_expression(&mut self, expr: &ArrowFunctionExpression<'a>) {
+        self.saved_loop_depths.push(self.loop_depth);
+        self.loop_depth = 0;
+        walk_arrow_function_expression(self, expr);
+        self.loop_depth = self.saved_loop_depths.pop().unwrap_or_default();
+    }
+
+    fn visit_for_statement(&mut self, stmt: &ForStatement<'a>) {
+        self.loop_depth += 1;
+        walk_for_statement(self, stmt);
+        self.loop_depth -= 1;
+    }
+
+    fn visit_for_of_statement(&mut self, stmt: &ForOfStatement<'a>) {
+        self.loop_depth += 1;
+        walk_for_of_statement(self, stmt);
+        self.loop_depth -= 1;
+    }
+
+    fn visit_for_in_statement(&mut self, stmt: &ForInStatement<'a>) {
+        self.loop_depth += 1;
+        walk_for_in_statement(self, stmt);
+        self.loop_depth -= 1;
+    }
+
+    fn visit_while_statement(&mut self, stmt: &WhileStatement<'a>) {
+        self.loop_depth += 1;
+        walk_while_statement(self, stmt);
+        self.loop_depth -= 1;
+    }
+
+    fn visit_do_while_statement(&mut self, stmt: &DoWhileStatement<'a>) {
+        self.loop_depth += 1;
+        walk_do_while_statement(self, stmt);
+        self.loop_depth -= 1;
+    }
+}
+
+impl BlockingApiFinder<'_, '_> {
+    /// Describe the call if it blocks the main thread
+    fn blocking_call(&self, expr: &CallExpression<'_>) -> Option<String> {
+        // `alert('saved')`, the global dialog and not a local function with the same name
+        if let Expression::Identifier(callee) = &expr.callee {
+            let name = callee.name.as_str();
+            if BLOCKING_DIALOGS.contains(&name) && resolve_symbol(self.semantic, callee).is_none() {
+                return Some(dialog_description(name));
+            }
+            return None;
+        }
+        let member = expr.callee.get_member_expr()?;
+        let method = member.static_property_name()?;
+        let object = member.object().get_inner_expression();
+
+        if method == "open" && is_synchronous_open(expr) && self.is_xhr(object) {
+            return Some("synchronous 'XMLHttpRequest.open(..., false)' blocks the main thread until the response arrives. drop the 'false' argument and handle the 'load' event or use 'fetch'".to_string());
+        }
+        if matches!(method, "write" | "writeln") && self.is_global(object, "document") {
+            return Some(format!("'document.{}' blocks HTML parsing and forces the browser to restart layout. insert content with DOM APIs like 'append' or 'insertAdjacentHTML'", method));
+        }
+        if self.loop_depth > 0
+            && STORAGE_METHODS.contains(&method)
+            && (self.is_global(object, "localStorage") || self.is_global(object, "sessionStorage"))
+        {
+            return Some(format!("'{}.{}' inside a loop accesses the storage synchronously in every iteration. read or write it once outside the loop and keep the value in memory", object_name(object), method));
+        }
+        if BLOCKING_DIALOGS.contains(&method) && self.is_global(object, "window") {
+            return Some(dialog_description(method));
+        }
+        None
+    }
+
+    /// Check if the object is an `XMLHttpRequest` instance
+    /// identifiers declared in the program are resolved to their declaration, unknown objects are assumed to be one
+    fn is_xhr(&self, object: &Expression<'_>) -> bool {
+        match object {
+            Expression::NewExpression(_) => is_new_xhr(object),
+            Expression::Identifier(identifier) => match resolve_symbol(self.semantic, identifier) {
+                Some(symbol_id) => self.is_xhr_symbol(symbol_id),
+                None => true,
+            },
+            _ => true,
+        }
+    }
+
+    /// Check if the variable is initialized or assigned with `new XMLHttpRequest()`
+    fn is_xhr_symbol(&self, symbol_id: SymbolId) -> bool {
+        if let AstKind::VariableDeclarator(declarator) =
+            self.semantic.symbol_declaration(symbol_id).kind()
+        {
+            if declarator.init.as_ref().is_some_and(is_new_xhr) {
+                return true;
+            }
+        }
+        let nodes = self.semantic.nodes();
+        self.semantic
+            .symbol_references(symbol_id)
+            .filter(|reference| reference.is_write())
+            .any(|reference| {
+                nodes
+                    .ancestors(reference.node_id())
+                    .find_map(|node_id| match nodes.kind(node_id) {
+                        AstKind::AssignmentExpression(assignment) => {
+                            Some(is_new_xhr(&assignment.right))
+                        }
+                        _ => None,
+                    })
+                    .unwrap_or(false)
+            })
+    }
+
+    /// Check if the object is the global with the given name and not shadowed by a local variable
+    fn is_global(&self, object: &Expression<'_>, name: &str) -> bool {
+        match object {
+            Expression::Identifier(identifier) => {
+                identifier.name == name && resolve_symbol(self.semantic, identifier).is_none()
+            }
+            // `window.localStorage`
+            _ => object.is_specific_member_access("window", name),
+        }
+    }
+}
+
+// region: helpers
+
+/// Check if the expression is `new XMLHttpRequest()`
+fn is_new_xhr(expr: &Expression<'_>) -> bool {
+    matches!(expr.get_inner_expression(), Expression::NewExpression(new) if new.callee.is_specific_id("XMLHttpRequest"))
+}
+
+/// Check if the call passes `false` as the `async` argument of `open(method, url, async)`
+fn is_synchronous_open(expr: &CallExpression<'_>) -> bool {
+    matches!(
+        expr.arguments.get(2).and_then(Argument::as_expression),
+        Some(Expression::BooleanLiteral(literal)) if !literal.value
+    )
+}
+
+/// Get the name of the storage object, e.g. `localStorage` for `window.localStorage`
+fn object_name<'a>(object: &'a Expression<'_>) -> &'a str {
+    match object {
+        Expression::Identifier(identifier) => identifier.name.as_str(),
+        _ => object
+            .get_member_expr()
+            .and_then(|member| member.static_property_name())
+            .unwrap_or("storage"),
+    }
+}
+
+fn dialog_description(name: &str) -> String {
+    format!("'{}' blocks the main thread until the user closes the dialog. show a non-blocking in-page dialog instead", name)
+}
+
+// endregion: helpers
+// region: tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::rules::js::apply_to_source;
+
+    #[test]
+    fn test_synchronous_xhr_is_resolved_with_semantic() {
+        let source_text = "const request = new XMLHttpRequest();
+            request.open('GET', '/api/user', false);
+            const popup = { open(url, name, sync) {} };
+            popup.open('/help', 'help', false);
+            let later;
+            later = new XMLHttpRequest();
+            later.open('GET', '/api/later', false);
+            request.open('GET', '/api/user');";
+        let result = apply_to_source(&BlockingApis, source_text);
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].line, 2);
+        assert_eq!(result[1].line, 7);
+    }
+
+    #[test]
+    fn test_document_write_and_dialogs() {
+        let source_text = "document.write('<p>hello</p>');
+            if (confirm('Delete?')) remove();
+            window.alert('done');
+            function test() { const alert = (message) => log(message); alert('local'); }";
+        let result = apply_to_source(&BlockingApis, source_text);
+        assert_eq!(result.len(), 3);
+        assert!(result[1].description.starts_with("'confirm'"));
+    }
+
+    #[test]
+    fn test_storage_access_only_reported_in_loops() {
+        let source_text = "const theme = localStorage.getItem('theme');
+            for (const key of keys) {
+                values.push(window.sessionStorage.getItem(key));
+            }
+            keys.forEach((key) => localStorage.removeItem(key));";
+        let result = apply_to_source(&BlockingApis, source_text);
+        assert_eq!(result.len(), 1);
+        assert!(result[0]
+            .description
+            .starts_with("'sessionStorage.getItem' inside a loop"));
+    }
+}
+// endregion: tests
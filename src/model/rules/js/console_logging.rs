@@ -0,0 +1,182 @@
+use crate::model::ctx::Ctx;
+use crate::model::rules::LineResult;
+use crate::model::rules::Severity;
+
+use oxc::ast::ast::{
+    ArrowFunctionExpression, CallExpression, DebuggerStatement, Expression, ExpressionStatement,
+};
+use oxc::ast::visit::walk::{
+    walk_arrow_function_expression, walk_call_expression, walk_expression_statement,
+};
+use oxc::ast::Visit;
+
+use super::{line_column, Rule};
+
+/// This rule is used to find leftover console calls and debugger statements
+#[derive(Debug)]
+pub struct ConsoleLogging {
+    /// whether `console.error` and `console.warn` are accepted as intentional error reporting
+    pub allow_error_and_warn: bool,
+    /// statement or method name, start, end, whether the code can be removed on its own
+    matches: Vec<(String, u32, u32, bool)>,
+}
+
+impl Default for ConsoleLogging {
+    fn default() -> Self {
+        ConsoleLogging {
+            allow_error_and_warn: true,
+            matches: vec![],
+        }
+    }
+}
+
+impl Rule for ConsoleLogging {
+    fn get_name(&self) -> &str {
+        "JS-Console-Logging"
+    }
+    fn get_description(&self) -> &str {
+        "leftover logging and debug statements cost CPU time in hot paths and ship extra bytes. remove them or strip them in the production build (e.g. terser 'drop_console')"
+    }
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        if let Ctx::JavaScriptCtx(js_ctx) = ctx {
+            let mut console_logging = ConsoleLogging {
+                allow_error_and_warn: self.allow_error_and_warn,
+                matches: vec![],
+            };
+            console_logging.visit_program(js_ctx.program);
+
+            let mut result = vec![];
+            for (name, start, end, removable) in &console_logging.matches {
+                let (line, column) = line_column(js_ctx.input, *start);
+                let severity = if name == "debugger" {
+                    Severity::Warning
+                } else {
+                    Severity::Info
+                };
+                let fix = if *removable {
+                    format!("can be removed (bytes {}..{})", start, end)
+                } else {
+                    "is part of an expression, remove it together with the code around it"
+                        .to_string()
+                };
+                result.push(LineResult {
+                    severity,
+                    line,
+                    column,
+                    classification: self.get_name().to_string(),
+                    description: format!("'{}' {}. {}", name, fix, self.get_description()),
+                });
+            }
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a> Visit<'a> for ConsoleLogging {
+    fn visit_expression_statement(&mut self, stmt: &ExpressionStatement<'a>) {
+        // a statement that only logs can be removed as a whole, including the semicolon
+        if let Expression::CallExpression(call) = &stmt.expression {
+            if let Some(name) = self.reported_console_call(call) {
+                self.matches
+                    .push((name, stmt.span.start, stmt.span.end, true));
+                // the arguments can still contain code to report, e.g. a callback with `debugger`
+                self.visit_arguments(&call.arguments);
+                return;
+            }
+        }
+        walk_expression_statement(self, stmt);
+    }
+
+    fn visit_call_expression(&mut self, expr: &CallExpression<'a>) {
+        // console calls used as an expression, e.g. `promise.then(() => console.log(x))`
+        if let Some(name) = self.reported_console_call(expr) {
+            self.matches
+                .push((name, expr.span.start, expr.span.end, false));
+        }
+        walk_call_expression(self, expr);
+    }
+
+    fn visit_debugger_statement(&mut self, stmt: &DebuggerStatement) {
+        self.matches
+            .push(("debugger".to_string(), stmt.span.start, stmt.span.end, true));
+    }
+
+    fn visit_arrow_function_expression(&mut self, expr: &ArrowFunctionExpression<'a>) {
+        // the body of `(x) => console.log(x)` is an expression, removing it would leave the arrow without a body
+        if let Some(body) = expr.get_expression() {
+            self.visit_formal_parameters(&expr.params);
+            self.visit_expression(body);
+        } else {
+            walk_arrow_function_expression(self, expr);
+        }
+    }
+}
+
+impl ConsoleLogging {
+    /// Get the full name like `console.log` if the call is a console call that should be reported
+    fn reported_console_call(&self, call: &CallExpression<'_>) -> Option<String> {
+        let member = call.callee.get_member_expr()?;
+        if !member.object().is_specific_id("console") {
+            return None;
+        }
+        let method = member.static_property_name()?;
+        if self.allow_error_and_warn && matches!(method, "error" | "warn") {
+            return None;
+        }
+        Some(format!("console.{}", method))
+    }
+}
+
+// region: tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::rules::js::apply_to_source;
+
+    #[test]
+    fn test_console_statements_are_reported_with_removable_span() {
+        let source_text = "console.log('start');\nrender();";
+        let result = apply_to_source(&ConsoleLogging::default(), source_text);
+        assert_eq!(result.len(), 1);
+        assert!(result[0]
+            .description
+            .starts_with("'console.log' can be removed (bytes 0..21)"));
+    }
+
+    #[test]
+    fn test_error_and_warn_are_allowed_by_default() {
+        let source_text = "console.error(err); console.warn('deprecated'); console.table(rows);";
+        let result = apply_to_source(&ConsoleLogging::default(), source_text);
+        assert_eq!(result.len(), 1);
+        let rule = ConsoleLogging {
+            allow_error_and_warn: false,
+            ..ConsoleLogging::default()
+        };
+        assert_eq!(apply_to_source(&rule, source_text).len(), 3);
+    }
+
+    #[test]
+    fn test_debugger_and_nested_console_calls() {
+        let source_text = "function handler(event) {
+                debugger;
+                return fetch(url).then((response) => console.debug(response));
+            }";
+        let result = apply_to_source(&ConsoleLogging::default(), source_text);
+        assert_eq!(result.len(), 2);
+        assert!(matches!(result[0].severity, Severity::Warning));
+        assert_eq!(result[1].line, 3);
+        assert!(result[1]
+            .description
+            .starts_with("'console.debug' is part of an expression"));
+
+        let source_text = "console.log(measure(() => { debugger; }));";
+        let result = apply_to_source(&ConsoleLogging::default(), source_text);
+        assert_eq!(result.len(), 2);
+        assert!(result[1]
+            .description
+            .starts_with("'debugger' can be removed"));
+    }
+}
+// endregion: tests
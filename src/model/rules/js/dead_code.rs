@@ -0,0 +1,428 @@
+use std::collections::HashSet;
+
+use crate::model::ctx::Ctx;
+use crate::model::rules::LineResult;
+use crate::model::rules::Severity;
+
+use oxc::ast::ast::{
+    ArrayExpressionElement, BindingPatternKind, BlockStatement, Expression, FunctionBody,
+    ObjectPropertyKind, Program, Statement, SwitchCase,
+};
+use oxc::ast::visit::walk::{
+    walk_block_statement, walk_function_body, walk_program, walk_switch_case,
+};
+use oxc::ast::{AstKind, Visit};
+use oxc::semantic::{Semantic, SymbolFlags, SymbolId};
+use oxc::span::{GetSpan, Span};
+use oxc::syntax::operator::UnaryOperator;
+
+use super::{line_column, Rule};
+
+/// This rule is used to find unused declarations and unreachable code
+pub struct DeadCode;
+
+impl Rule for DeadCode {
+    fn get_name(&self) -> &str {
+        "JS-Dead-Code"
+    }
+    fn get_description(&self) -> &str {
+        "dead code is downloaded and parsed for nothing. remove it or let the bundler tree-shake it"
+    }
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        if let Ctx::JavaScriptCtx(js_ctx) = ctx {
+            // top level declarations of a classic script are globals other scripts may use
+            let is_script = !js_ctx
+                .program
+                .body
+                .iter()
+                .any(|statement| statement.as_module_declaration().is_some());
+
+            // description, removable span
+            let mut matches: Vec<(String, Span)> = vec![];
+            for symbol_id in js_ctx.semantic.symbols().iter() {
+                if let Some((kind, span)) = unused_declaration(js_ctx.semantic, symbol_id) {
+                    let name = js_ctx.semantic.symbols().get_name(symbol_id);
+                    let global = is_script
+                        && js_ctx.semantic.symbol_scope(symbol_id)
+                            == js_ctx.semantic.scopes().root_scope_id();
+                    let note = if global {
+                        " unless another script uses it"
+                    } else {
+                        ""
+                    };
+                    matches.push((
+                        format!(
+                            "{} '{}' is never used and can be removed{} ({} bytes). {}",
+                            kind,
+                            name,
+                            note,
+                            span.size(),
+                            self.get_description()
+                        ),
+                        span,
+                    ));
+                }
+            }
+
+            let mut finder = UnreachableFinder::new(js_ctx.semantic);
+            finder.visit_program(js_ctx.program);
+            for (jump, span) in finder.matches {
+                let cause = match jump {
+                    Some(jump) => format!("after '{}'", jump),
+                    None => {
+                        "after a statement that returns, throws or jumps on every path".to_string()
+                    }
+                };
+                matches.push((
+                    format!(
+                        "code {} is never executed ({} bytes). {}",
+                        cause,
+                        span.size(),
+                        self.get_description()
+                    ),
+                    span,
+                ));
+            }
+            matches.sort_by_key(|(_, span)| span.start);
+
+            let mut result = vec![];
+            for (description, span) in matches {
+                let (line, column) = line_column(js_ctx.input, span.start);
+                result.push(LineResult {
+                    severity: Severity::Info,
+                    line,
+                    column,
+                    classification: self.get_name().to_string(),
+                    description,
+                });
+            }
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+/// Visitor that finds statements the control flow graph cannot reach, like the code after `return` or after an `if` whose branches all return
+struct UnreachableFinder {
+    /// spans of the statements in unreachable basic blocks
+    unreachable: HashSet<Span>,
+    /// jumping statement right before the code if there is one, span of the unreachable statements
+    matches: Vec<(Option<&'static str>, Span)>,
+}
+
+impl<'a> Visit<'a> for UnreachableFinder {
+    fn visit_program(&mut self, program: &Program<'a>) {
+        self.check_statements(&program.body);
+        walk_program(self, program);
+    }
+
+    fn visit_function_body(&mut self, body: &FunctionBody<'a>) {
+        self.check_statements(&body.statements);
+        walk_function_body(self, body);
+    }
+
+    fn visit_block_statement(&mut self, stmt: &BlockStatement<'a>) {
+        self.check_statements(&stmt.body);
+        walk_block_statement(self, stmt);
+    }
+
+    fn visit_switch_case(&mut self, case: &SwitchCase<'a>) {
+        self.check_statements(&case.consequent);
+        walk_switch_case(self, case);
+    }
+}
+
+impl UnreachableFinder {
+    fn new(semantic: &Semantic<'_>) -> Self {
+        // without a control flow graph nothing is reported
+        let unreachable = semantic
+            .cfg()
+            .map(|cfg| {
+                semantic
+                    .nodes()
+                    .iter()
+                    .filter(|node| cfg.basic_block(node.cfg_id()).unreachable)
+                    .map(|node| node.kind().span())
+                    .collect()
+            })
+            .unwrap_or_default();
+        UnreachableFinder {
+            unreachable,
+            matches: vec![],
+        }
+    }
+
+    fn check_statements(&mut self, statements: &[Statement<'_>]) {
+        // function declarations are hoisted and can still be called
+        let is_skipped = |statement: &Statement<'_>| {
+            matches!(
+                statement,
+                Statement::FunctionDeclaration(_) | Statement::EmptyStatement(_)
+            )
+        };
+        let is_unreachable = |statement: &Statement<'_>| {
+            !is_skipped(statement) && self.unreachable.contains(&statement.span())
+        };
+        let Some(index) = statements.iter().position(is_unreachable) else {
+            return;
+        };
+        let first = statements[index].span();
+        // statements nested in code that is already reported
+        if self
+            .matches
+            .iter()
+            .any(|(_, span)| span.start <= first.start && first.end <= span.end)
+        {
+            return;
+        }
+        let last = statements[index..]
+            .iter()
+            .take_while(|statement| is_skipped(statement) || is_unreachable(statement))
+            .filter(|statement| !is_skipped(statement))
+            .last()
+            .map_or(first, |statement| statement.span());
+        let jump = index
+            .checked_sub(1)
+            .and_then(|previous| jump_name(&statements[previous]));
+        self.matches.push((jump, Span::new(first.start, last.end)));
+    }
+}
+
+// region: helpers
+
+fn jump_name(statement: &Statement<'_>) -> Option<&'static str> {
+    match statement {
+        Statement::ReturnStatement(_) => Some("return"),
+        Statement::ThrowStatement(_) => Some("throw"),
+        Statement::BreakStatement(_) => Some("break"),
+        Statement::ContinueStatement(_) => Some("continue"),
+        _ => None,
+    }
+}
+
+/// Get the kind and the removable span of the declaration if the symbol is never read
+/// exports, parameters and named function expressions are not reported
+fn unused_declaration(
+    semantic: &Semantic<'_>,
+    symbol_id: SymbolId,
+) -> Option<(&'static str, Span)> {
+    let symbols = semantic.symbols();
+    if symbols.get_flags(symbol_id).contains(SymbolFlags::Export) {
+        return None;
+    }
+    let nodes = semantic.nodes();
+    let declaration = symbols.get_declaration(symbol_id);
+    let (kind, span) = match nodes.kind(declaration) {
+        AstKind::VariableDeclarator(declarator) => {
+            let is_identifier =
+                matches!(declarator.id.kind, BindingPatternKind::BindingIdentifier(_));
+            // removing `const a = save();` would also remove the call
+            if is_identifier && declarator.init.as_ref().is_some_and(has_side_effects) {
+                return None;
+            }
+            // the binding of `for (const item of items)` cannot be removed without the loop
+            let in_loop_head = nodes
+                .parent_id(declaration)
+                .and_then(|parent| nodes.parent_kind(parent))
+                .is_some_and(|kind| {
+                    matches!(
+                        kind,
+                        AstKind::ForOfStatement(_) | AstKind::ForInStatement(_)
+                    )
+                });
+            if in_loop_head {
+                return None;
+            }
+            let span = match (&declarator.id.kind, nodes.parent_kind(declaration)) {
+                // `const a = 1;` is removed as a whole statement, `const a = 1, b = 2;` only the declarator
+                (
+                    BindingPatternKind::BindingIdentifier(_),
+                    Some(AstKind::VariableDeclaration(declaration)),
+                ) if declaration.declarations.len() == 1 => declaration.span,
+                (BindingPatternKind::BindingIdentifier(_), _) => declarator.span,
+                // a single name of a destructuring pattern
+                _ => symbols.get_span(symbol_id),
+            };
+            ("variable", span)
+        }
+        AstKind::Function(func) if func.is_declaration() => ("function", func.span),
+        AstKind::Class(class) if class.is_declaration() => ("class", class.span),
+        kind @ (AstKind::ImportSpecifier(_)
+        | AstKind::ImportDefaultSpecifier(_)
+        | AstKind::ImportNamespaceSpecifier(_)) => {
+            let span = match nodes.parent_kind(declaration) {
+                Some(AstKind::ImportDeclaration(import))
+                    if import
+                        .specifiers
+                        .as_ref()
+                        .is_some_and(|specifiers| specifiers.len() == 1) =>
+                {
+                    import.span
+                }
+                _ => kind.span(),
+            };
+            ("import", span)
+        }
+        _ => return None,
+    };
+    // references inside the declaration itself, like a recursive call, do not count as a use
+    let used = semantic.symbol_references(symbol_id).any(|reference| {
+        let reference_span = semantic.reference_span(reference);
+        reference.is_read()
+            && !(span.start <= reference_span.start && reference_span.end <= span.end)
+    });
+    if used {
+        None
+    } else {
+        Some((kind, span))
+    }
+}
+
+/// Check if evaluating the expression may do more than produce a value, like a call, `new` or `await`
+fn has_side_effects(expr: &Expression<'_>) -> bool {
+    match expr {
+        Expression::BooleanLiteral(_)
+        | Expression::NullLiteral(_)
+        | Expression::NumericLiteral(_)
+        | Expression::BigIntLiteral(_)
+        | Expression::RegExpLiteral(_)
+        | Expression::StringLiteral(_)
+        | Expression::Identifier(_)
+        | Expression::ThisExpression(_)
+        | Expression::ArrowFunctionExpression(_)
+        | Expression::FunctionExpression(_) => false,
+        Expression::TemplateLiteral(template) => template.expressions.iter().any(has_side_effects),
+        Expression::ArrayExpression(array) => array.elements.iter().any(|element| match element {
+            ArrayExpressionElement::SpreadElement(_) => true,
+            ArrayExpressionElement::Elision(_) => false,
+            element => element.as_expression().is_some_and(has_side_effects),
+        }),
+        Expression::ObjectExpression(object) => {
+            object.properties.iter().any(|property| match property {
+                ObjectPropertyKind::ObjectProperty(property) => {
+                    property.computed || has_side_effects(&property.value)
+                }
+                ObjectPropertyKind::SpreadProperty(_) => true,
+            })
+        }
+        Expression::UnaryExpression(unary) => {
+            unary.operator == UnaryOperator::Delete || has_side_effects(&unary.argument)
+        }
+        Expression::BinaryExpression(binary) => {
+            has_side_effects(&binary.left) || has_side_effects(&binary.right)
+        }
+        Expression::LogicalExpression(logical) => {
+            has_side_effects(&logical.left) || has_side_effects(&logical.right)
+        }
+        Expression::ConditionalExpression(conditional) => {
+            has_side_effects(&conditional.test)
+                || has_side_effects(&conditional.consequent)
+                || has_side_effects(&conditional.alternate)
+        }
+        Expression::ParenthesizedExpression(parenthesized) => {
+            has_side_effects(&parenthesized.expression)
+        }
+        Expression::StaticMemberExpression(member) => has_side_effects(&member.object),
+        _ => true,
+    }
+}
+
+// endregion: helpers
+// region: tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::rules::js::apply_to_source;
+
+    #[test]
+    fn test_unused_declarations_are_reported_with_removable_bytes() {
+        let source_text = "import { debounce, throttle } from 'lodash';
+            import moment from 'moment';
+            const unused = 42;
+            function factorial(n) { return n <= 1 ? 1 : n * factorial(n - 1); }
+            let count = 0;
+            count = 1;
+            export function run(input) { return throttle(input); }";
+        let result = apply_to_source(&DeadCode, source_text);
+        assert_eq!(result.len(), 5);
+        assert!(result[0].description.starts_with("import 'debounce'"));
+        assert!(result[1]
+            .description
+            .starts_with("import 'moment' is never used and can be removed (28 bytes)"));
+        assert!(result[2].description.contains("(18 bytes)"));
+        assert!(result[3].description.starts_with("function 'factorial'"));
+        assert!(result[4].description.starts_with("variable 'count'"));
+    }
+
+    #[test]
+    fn test_side_effects_and_loop_bindings_are_ignored() {
+        let source_text = "export async function init(config) {
+                const saved = save(config);
+                const widget = new Widget(config);
+                const response = await fetch(config.url);
+                const { a, b } = config;
+                const options = { retries: 3, delay: config.delay * 2 };
+                for (const item of config.items) { count++; }
+                for (const key in config) { count++; }
+                return a;
+            }";
+        let result = apply_to_source(&DeadCode, source_text);
+        assert_eq!(result.len(), 2);
+        assert!(result[0].description.starts_with("variable 'b'"));
+        assert!(result[1].description.starts_with("variable 'options'"));
+    }
+
+    #[test]
+    fn test_globals_of_classic_scripts_are_marked() {
+        let source_text = "function onSubmit() { send(); }
+            (function () { const helper = () => {}; })();";
+        let result = apply_to_source(&DeadCode, source_text);
+        assert_eq!(result.len(), 2);
+        assert!(result[0]
+            .description
+            .contains("unless another script uses it"));
+        assert!(!result[1]
+            .description
+            .contains("unless another script uses it"));
+    }
+
+    #[test]
+    fn test_code_after_return_and_throw_is_reported() {
+        let source_text = "export function parse(value) {
+                if (!value) {
+                    throw new Error('empty');
+                    console.log('never');
+                }
+                return normalize(value);
+                cleanup();
+                function normalize(v) { return v.trim(); }
+            }";
+        let result = apply_to_source(&DeadCode, source_text);
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].line, 4);
+        assert!(result[0]
+            .description
+            .starts_with("code after 'throw' is never executed (21 bytes)"));
+        assert_eq!(result[1].line, 7);
+    }
+
+    #[test]
+    fn test_code_after_branches_that_all_return_is_reported() {
+        let source_text = "export function sign(value) {
+                if (value < 0) return -1; else return 1;
+                console.log('never');
+            }
+            export function load() {
+                try { return fetch(url); } catch (error) { report(error); }
+                return null;
+            }";
+        let result = apply_to_source(&DeadCode, source_text);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].line, 3);
+        assert!(result[0]
+            .description
+            .starts_with("code after a statement that returns, throws or jumps on every path"));
+    }
+}
+// endregion: tests
@@ -6,26 +6,22 @@ use oxc::ast::ast::Expression::BinaryExpression;
 use oxc::ast::ast::Expression::CallExpression;
 use oxc::ast::ast::Expression::FunctionExpression;
 use oxc::ast::ast::Expression::Identifier;
+use oxc::ast::ast::{FormalParameters, IdentifierReference};
 
-use super::Rule;
+use super::{line_column, resolve_symbol, Rule};
 use crate::model::ctx::Ctx;
 use oxc::ast::ast::Statement::ExpressionStatement;
 use oxc::ast::ast::Statement::ReturnStatement;
 use oxc::ast::visit::walk::walk_call_expression;
 use oxc::ast::Visit;
+use oxc::semantic::{Semantic, SymbolId};
 
 use oxc::span::GetSpan;
 use oxc::syntax::operator::BinaryOperator::Equality;
 use oxc::syntax::operator::BinaryOperator::StrictEquality;
 /// This rule is used to find filter method calls that remove duplicates from an array
-#[derive(Debug, Default)]
-pub struct Duplicates {
-    /// function name, start, end
-    matches: Vec<(String, u32, u32)>,
-    array_identifier: String,
-    item: String,
-    pos: String,
-}
+#[derive(Debug)]
+pub struct Duplicates;
 
 impl Rule for Duplicates {
     fn get_name(&self) -> &str {
@@ -38,12 +34,12 @@ impl Rule for Duplicates {
         if let Ctx::JavaScriptCtx(js_ctx) = ctx {
             let ast = js_ctx.program;
             let mut result = vec![];
-            let mut duplicates: Duplicates = Duplicates::default();
+            let mut duplicates = DuplicateFinder::new(js_ctx.semantic);
             duplicates.visit_program(ast);
 
             // iterate over the matches and create LineResult objects
             for (_function_name, start, _end) in &duplicates.matches {
-                let (line, column) = Duplicates::line_column(js_ctx.input, *start);
+                let (line, column) = line_column(js_ctx.input, *start);
                 let classification = self.get_name().to_string();
                 let description = self.get_description().to_string();
                 let line_result = LineResult {
@@ -62,34 +58,40 @@ impl Rule for Duplicates {
     }
 }
 
-impl<'a> Visit<'a> for Duplicates {
+/// what the filtered array refers to, globals are compared by name
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ArrayBinding {
+    Symbol(SymbolId),
+    Global(String),
+}
+
+/// Visitor that finds the filter calls, using the semantic model to resolve the identifiers to their bindings
+struct DuplicateFinder<'s, 'a> {
+    semantic: &'s Semantic<'a>,
+    /// function name, start, end
+    matches: Vec<(String, u32, u32)>,
+    /// the array 'filter' is called on
+    array: Option<ArrayBinding>,
+    /// the 'item', 'pos' and 'self' parameters of the filter callback
+    item: Option<SymbolId>,
+    pos: Option<SymbolId>,
+    array_param: Option<SymbolId>,
+}
+
+impl<'a> Visit<'a> for DuplicateFinder<'_, 'a> {
     // entrypoint for the visitor pattern
     fn visit_call_expression(&mut self, expr: &oxc::ast::ast::CallExpression<'a>) {
         // extract the method name, array identifier and arguments from the call expression
-        let (method_name, array_identifier, arguments) = {
-            (
-                &expr
-                    .callee
-                    .get_inner_expression()
-                    .get_member_expr()
-                    .unwrap()
-                    .static_property_name(),
-                &expr
-                    .callee
-                    .get_inner_expression()
-                    .get_member_expr()
-                    .unwrap()
-                    .object()
-                    .get_identifier_reference()
-                    .unwrap()
-                    .name,
-                &expr.arguments,
-            )
-        };
+        let member = expr.callee.get_inner_expression().get_member_expr();
+        let method_name = member.and_then(|member| member.static_property_name());
+        let array_identifier = member.and_then(|member| member.object().get_identifier_reference());
+        let arguments = &expr.arguments;
         // check if the method name is 'filter' and has the correct number of arguments
-        if method_name.unwrap_or("not filter") == "filter" && arguments.len() == 1 {
-            // assign the array identifier for later matching
-            self.array_identifier = array_identifier.to_string();
+        if let (Some("filter"), Some(array_identifier), 1) =
+            (method_name, array_identifier, arguments.len())
+        {
+            // resolve the array identifier for later matching
+            self.array = Some(self.array_binding(array_identifier));
             // match whether it is a function expression  call or an arrow function expression call
             match &arguments[0].to_expression() {
                 // handle arrow function expression
@@ -108,36 +110,41 @@ impl<'a> Visit<'a> for Duplicates {
     }
 }
 
-impl Duplicates {
+impl<'s, 'a> DuplicateFinder<'s, 'a> {
+    fn new(semantic: &'s Semantic<'a>) -> Self {
+        DuplicateFinder {
+            semantic,
+            matches: vec![],
+            array: None,
+            item: None,
+            pos: None,
+            array_param: None,
+        }
+    }
+
     // region: handlers
 
     /// Handle the arrow function expression and check its bodies validity for further processing
-    fn handle_arrow_function_expression<'a>(
+    fn handle_arrow_function_expression(
         &mut self,
-        arrow_function_expression: &'a oxc::allocator::Box<
-            'a,
-            oxc::ast::ast::ArrowFunctionExpression<'a>,
-        >,
+        arrow_function_expression: &oxc::ast::ast::ArrowFunctionExpression<'_>,
     ) {
         let function_body = &arrow_function_expression.body;
         if is_valid_expression_statement(arrow_function_expression, function_body) {
-            self.extract_binding_identifiers_from_arrow_function(arrow_function_expression);
+            self.extract_binding_identifiers(&arrow_function_expression.params);
             self.handle_expression_statement(function_body);
         } else if is_valid_return_statement(arrow_function_expression, function_body) {
-            self.extract_binding_identifiers_from_arrow_function(arrow_function_expression);
+            self.extract_binding_identifiers(&arrow_function_expression.params);
             self.handle_return_statement(function_body);
         };
     }
 
     /// Handle the function expression and check its bodies validity for further processing
-    fn handle_function_expression<'a>(
-        &mut self,
-        function_expression: &'a oxc::allocator::Box<'a, oxc::ast::ast::Function<'a>>,
-    ) {
+    fn handle_function_expression(&mut self, function_expression: &oxc::ast::ast::Function<'_>) {
         if function_expression.params.items.len() >= 2 && function_expression.body.is_some() {
-            self.extract_binding_identifiers_from_function_expression(function_expression);
+            self.extract_binding_identifiers(&function_expression.params);
             if let Some(body) = &function_expression.body {
-                if body.statements.len() > 0 {
+                if !body.statements.is_empty() {
                     self.handle_return_statement(body);
                 }
             }
@@ -145,10 +152,7 @@ impl Duplicates {
     }
 
     /// Handle the expression statement and the internal binary expression
-    fn handle_expression_statement<'a>(
-        &mut self,
-        function_body: &'a oxc::allocator::Box<'a, oxc::ast::ast::FunctionBody<'a>>,
-    ) {
+    fn handle_expression_statement(&mut self, function_body: &oxc::ast::ast::FunctionBody<'_>) {
         if let ExpressionStatement(binary_expression) = &function_body.statements[0] {
             if let BinaryExpression(binary_expression) = &binary_expression.expression {
                 self.handle_binary_expression(binary_expression);
@@ -157,10 +161,7 @@ impl Duplicates {
     }
 
     /// Handle the return statement and the internal binary expression
-    fn handle_return_statement<'a>(
-        &mut self,
-        function_body: &'a oxc::allocator::Box<'a, oxc::ast::ast::FunctionBody<'a>>,
-    ) {
+    fn handle_return_statement(&mut self, function_body: &oxc::ast::ast::FunctionBody<'_>) {
         if let ReturnStatement(return_statement) = &function_body.statements[0] {
             if let Some(BinaryExpression(binary_expression)) = &return_statement.argument {
                 self.handle_binary_expression(binary_expression);
@@ -169,9 +170,9 @@ impl Duplicates {
     }
 
     /// Handle the binary expression and check if it is a valid indexOf method call
-    fn handle_binary_expression<'a>(
+    fn handle_binary_expression(
         &mut self,
-        binary_expression: &'a oxc::allocator::Box<'a, oxc::ast::ast::BinaryExpression<'a>>,
+        binary_expression: &oxc::ast::ast::BinaryExpression<'_>,
     ) {
         if (binary_expression.operator == Equality || binary_expression.operator == StrictEquality)
             && ((binary_expression.right.is_identifier_reference()
@@ -181,13 +182,13 @@ impl Duplicates {
         {
             if let CallExpression(call_expression) = &binary_expression.right {
                 if let Some(index) = binary_expression.left.get_identifier_reference() {
-                    if index.name == self.pos && call_expression.arguments.len() == 1 {
+                    if self.refers_to(index, self.pos) && call_expression.arguments.len() == 1 {
                         self.handle_indexof_call_expression(call_expression);
                     }
                 }
             } else if let CallExpression(call_expression) = &binary_expression.left {
                 if let Some(index) = binary_expression.right.get_identifier_reference() {
-                    if index.name == self.pos && call_expression.arguments.len() == 1 {
+                    if self.refers_to(index, self.pos) && call_expression.arguments.len() == 1 {
                         self.handle_indexof_call_expression(call_expression);
                     }
                 }
@@ -196,17 +197,19 @@ impl Duplicates {
     }
 
     /// Handle the call expression and check if it is a valid indexOf method call
-    fn handle_indexof_call_expression<'a>(
+    fn handle_indexof_call_expression(
         &mut self,
-        call_expression: &'a oxc::allocator::Box<'a, oxc::ast::ast::CallExpression<'a>>,
+        call_expression: &oxc::ast::ast::CallExpression<'_>,
     ) {
         if let Some(callee) = call_expression.callee.get_member_expr() {
-            if let Some(static_property_name) = callee.static_property_name() {
-                // check method call is indexOf and the object is the array identifier and the argument is the item identifier
+            if let (Some(static_property_name), Some(target)) = (
+                callee.static_property_name(),
+                get_function_target_identifier(call_expression),
+            ) {
+                // check method call is indexOf on the filtered array or the callbacks 'self' parameter and the argument is the item
                 if static_property_name == "indexOf"
-                    && (*get_function_target_identifier_name(call_expression)
-                        == self.array_identifier
-                        || *get_function_target_identifier_name(call_expression) == "self")
+                    && (self.array.as_ref() == Some(&self.array_binding(target))
+                        || self.refers_to(target, self.array_param))
                     && self.has_valid_param(call_expression)
                 {
                     // handle indexOf method call
@@ -224,108 +227,72 @@ impl Duplicates {
 
     // region: helpers
     /// Check if the call expression has valid 'item' parameter
-    fn has_valid_param<'a>(
-        &mut self,
-        call_expression: &oxc::allocator::Box<'a, oxc::ast::ast::CallExpression<'a>>,
-    ) -> bool {
+    fn has_valid_param(&self, call_expression: &oxc::ast::ast::CallExpression<'_>) -> bool {
         if let Identifier(identifier) = call_expression.arguments[0].to_expression() {
-            return identifier.name == self.item && call_expression.arguments.len() == 1;
+            return self.refers_to(identifier, self.item) && call_expression.arguments.len() == 1;
         }
         false
     }
 
-    /// Get the line and column of a given start position in the given input string
-    fn line_column(input: &str, start: u32) -> (i32, i32) {
-        let mut line = 1;
-        let mut column = 0;
-        for (i, c) in input.chars().enumerate() {
-            if i as u32 == start {
-                break;
-            }
-            if c == '\n' {
-                line += 1;
-                column = 1;
-            } else {
-                column += 1;
-            }
-        }
-        (line, column)
+    /// Check if the identifier refers to the given binding
+    fn refers_to(&self, identifier: &IdentifierReference<'_>, symbol_id: Option<SymbolId>) -> bool {
+        symbol_id.is_some() && resolve_symbol(self.semantic, identifier) == symbol_id
     }
 
-    /// Extract the binding identifiers from the function expression for item and pos for later matching
-    fn extract_binding_identifiers_from_function_expression<'a>(
-        &mut self,
-        function_expression: &oxc::allocator::Box<'a, oxc::ast::ast::Function<'a>>,
-    ) {
-        if let BindingIdentifier(binding_identifier) =
-            &function_expression.params.items[0].pattern.kind
-        {
-            self.item = binding_identifier.name.to_string();
-        }
-        if let BindingIdentifier(binding_identifier) =
-            &function_expression.params.items[1].pattern.kind
-        {
-            self.pos = binding_identifier.name.to_string();
+    /// Resolve the array identifier to its declaration, undeclared arrays are matched by name
+    fn array_binding(&self, identifier: &IdentifierReference<'_>) -> ArrayBinding {
+        match resolve_symbol(self.semantic, identifier) {
+            Some(symbol_id) => ArrayBinding::Symbol(symbol_id),
+            None => ArrayBinding::Global(identifier.name.to_string()),
         }
     }
 
-    /// Extract the binding identifiers from the arrow function expression for item and pos for later matching
-    fn extract_binding_identifiers_from_arrow_function<'a>(
-        &mut self,
-        arrow_function_expression: &oxc::allocator::Box<
-            'a,
-            oxc::ast::ast::ArrowFunctionExpression<'a>,
-        >,
-    ) {
-        if let BindingIdentifier(binding_identifier) =
-            &arrow_function_expression.params.items[0].pattern.kind
+    /// Extract the bindings of the callback parameters for item, pos and self for later matching
+    fn extract_binding_identifiers(&mut self, params: &FormalParameters<'_>) {
+        let binding = |index: usize| match params.items.get(index).map(|param| &param.pattern.kind)
         {
-            self.item = binding_identifier.name.to_string();
-        }
-        if let BindingIdentifier(binding_identifier) =
-            &arrow_function_expression.params.items[1].pattern.kind
-        {
-            self.pos = binding_identifier.name.to_string();
-        }
+            Some(BindingIdentifier(binding_identifier)) => binding_identifier.symbol_id.get(),
+            _ => None,
+        };
+        self.item = binding(0);
+        self.pos = binding(1);
+        self.array_param = binding(2);
     }
     // endregion: helpers
 }
 
 // region: helpers
 
-/// Get the name (identifier) of the function target
-fn get_function_target_identifier_name<'a>(
+/// Get the identifier the function is called on, `None` if it is not called on an identifier
+fn get_function_target_identifier<'a>(
     call_expression: &'a oxc::ast::ast::CallExpression<'a>,
-) -> &'a oxc::span::Atom {
-    &call_expression
+) -> Option<&'a IdentifierReference<'a>> {
+    call_expression
         .callee
         .get_inner_expression()
-        .get_member_expr()
-        .unwrap()
+        .get_member_expr()?
         .object()
         .get_identifier_reference()
-        .unwrap()
-        .name
 }
 
 /// Check if the arrow function expression has the correct number of parameters and is a non empty return statement
-fn is_valid_return_statement<'a>(
-    arrow_function_expression: &oxc::allocator::Box<'a, oxc::ast::ast::ArrowFunctionExpression<'a>>,
-    function_body: &oxc::allocator::Box<'a, oxc::ast::ast::FunctionBody<'a>>,
+fn is_valid_return_statement(
+    arrow_function_expression: &oxc::ast::ast::ArrowFunctionExpression<'_>,
+    function_body: &oxc::ast::ast::FunctionBody<'_>,
 ) -> bool {
     arrow_function_expression.params.items.len() >= 2
         && !arrow_function_expression.expression
-        && function_body.statements.len() > 0
+        && !function_body.statements.is_empty()
 }
 
 /// Check if the arrow function expression has the correct number of parameters and is a non empty expression statement
-fn is_valid_expression_statement<'a>(
-    arrow_function_expression: &oxc::allocator::Box<'a, oxc::ast::ast::ArrowFunctionExpression<'a>>,
-    function_body: &oxc::allocator::Box<'a, oxc::ast::ast::FunctionBody<'a>>,
+fn is_valid_expression_statement(
+    arrow_function_expression: &oxc::ast::ast::ArrowFunctionExpression<'_>,
+    function_body: &oxc::ast::ast::FunctionBody<'_>,
 ) -> bool {
     arrow_function_expression.params.items.len() >= 2
         && arrow_function_expression.expression
-        && function_body.statements.len() > 0
+        && !function_body.statements.is_empty()
 }
 // endregion: helpers
 // region: tests
@@ -334,8 +301,10 @@ mod tests {
     use super::*;
     use oxc::allocator::Allocator;
 
+    use crate::model::rules::js::apply_to_source;
     use oxc::ast::Visit;
     use oxc::parser::Parser;
+    use oxc::semantic::SemanticBuilder;
     use oxc::span::SourceType;
 
     /*
@@ -390,13 +359,11 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
 
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let mut ast_pass = DuplicateFinder::new(&semantic);
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 1);
         assert_eq!(ast_pass.matches[0].0, "pattern found");
@@ -410,13 +377,11 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
 
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let mut ast_pass = DuplicateFinder::new(&semantic);
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 1);
         assert_eq!(ast_pass.matches[0].0, "pattern found");
@@ -430,13 +395,11 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
 
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let mut ast_pass = DuplicateFinder::new(&semantic);
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 1);
         assert_eq!(ast_pass.matches[0].0, "pattern found");
@@ -450,13 +413,11 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
 
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let mut ast_pass = DuplicateFinder::new(&semantic);
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 1);
         assert_eq!(ast_pass.matches[0].0, "pattern found");
@@ -470,13 +431,11 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
 
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let mut ast_pass = DuplicateFinder::new(&semantic);
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 1);
         assert_eq!(ast_pass.matches[0].0, "pattern found");
@@ -490,13 +449,11 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
 
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let mut ast_pass = DuplicateFinder::new(&semantic);
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 1);
         assert_eq!(ast_pass.matches[0].0, "pattern found");
@@ -510,13 +467,11 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
 
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let mut ast_pass = DuplicateFinder::new(&semantic);
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 1);
         assert_eq!(ast_pass.matches[0].0, "pattern found");
@@ -530,13 +485,11 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
 
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let mut ast_pass = DuplicateFinder::new(&semantic);
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 1);
         assert_eq!(ast_pass.matches[0].0, "pattern found");
@@ -550,13 +503,11 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
 
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let mut ast_pass = DuplicateFinder::new(&semantic);
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 1);
         assert_eq!(ast_pass.matches[0].0, "pattern found");
@@ -570,13 +521,11 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
 
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let mut ast_pass = DuplicateFinder::new(&semantic);
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 1);
         assert_eq!(ast_pass.matches[0].0, "pattern found");
@@ -590,13 +539,11 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
 
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let mut ast_pass = DuplicateFinder::new(&semantic);
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 1);
         assert_eq!(ast_pass.matches[0].0, "pattern found");
@@ -610,13 +557,11 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
 
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let mut ast_pass = DuplicateFinder::new(&semantic);
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 1);
         assert_eq!(ast_pass.matches[0].0, "pattern found");
@@ -630,13 +575,11 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
 
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let mut ast_pass = DuplicateFinder::new(&semantic);
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 1);
         assert_eq!(ast_pass.matches[0].0, "pattern found");
@@ -650,13 +593,11 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
 
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let mut ast_pass = DuplicateFinder::new(&semantic);
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 1);
         assert_eq!(ast_pass.matches[0].0, "pattern found");
@@ -670,13 +611,11 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
 
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let mut ast_pass = DuplicateFinder::new(&semantic);
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 1);
         assert_eq!(ast_pass.matches[0].0, "pattern found");
@@ -690,13 +629,11 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
 
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let mut ast_pass = DuplicateFinder::new(&semantic);
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 1);
         assert_eq!(ast_pass.matches[0].0, "pattern found");
@@ -711,13 +648,11 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
 
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let mut ast_pass = DuplicateFinder::new(&semantic);
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 1);
         assert_eq!(ast_pass.matches[0].0, "pattern found");
@@ -732,13 +667,11 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
 
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let mut ast_pass = DuplicateFinder::new(&semantic);
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 1);
         assert_eq!(ast_pass.matches[0].0, "pattern found");
@@ -753,12 +686,10 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
+        let mut ast_pass = DuplicateFinder::new(&semantic);
 
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 1);
@@ -774,12 +705,10 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
+        let mut ast_pass = DuplicateFinder::new(&semantic);
 
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 1);
@@ -795,12 +724,10 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
+        let mut ast_pass = DuplicateFinder::new(&semantic);
 
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 1);
@@ -816,12 +743,10 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
+        let mut ast_pass = DuplicateFinder::new(&semantic);
 
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 1);
@@ -837,12 +762,10 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
+        let mut ast_pass = DuplicateFinder::new(&semantic);
 
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 1);
@@ -858,12 +781,10 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
+        let mut ast_pass = DuplicateFinder::new(&semantic);
 
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 1);
@@ -878,13 +799,11 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
 
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let mut ast_pass = DuplicateFinder::new(&semantic);
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 0);
     }
@@ -897,12 +816,10 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
+        let mut ast_pass = DuplicateFinder::new(&semantic);
 
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 0);
@@ -916,13 +833,11 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
 
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let mut ast_pass = DuplicateFinder::new(&semantic);
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 0);
     }
@@ -935,13 +850,11 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
 
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let mut ast_pass = DuplicateFinder::new(&semantic);
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 0);
     }
@@ -955,13 +868,11 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
 
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let mut ast_pass = DuplicateFinder::new(&semantic);
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 0);
     }
@@ -975,12 +886,10 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
+        let mut ast_pass = DuplicateFinder::new(&semantic);
 
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 0);
@@ -1024,15 +933,27 @@ mod tests {
         let source_type = SourceType::from_path("javscript.js").unwrap();
         let ret = Parser::new(&allocator, source_text, source_type).parse();
         let program = ret.program;
+        let semantic = SemanticBuilder::new(source_text, source_type)
+            .build(&program)
+            .semantic;
 
-        let mut ast_pass = Duplicates {
-            matches: vec![],
-            array_identifier: String::from(""),
-            item: String::from(""),
-            pos: String::from(""),
-        };
+        let mut ast_pass = DuplicateFinder::new(&semantic);
         ast_pass.visit_program(&program);
         assert_eq!(ast_pass.matches.len(), 24);
     }
+
+    #[test]
+    fn test_apply_resolves_bindings_instead_of_names() {
+        let source_text = "const array = load();
+            let uniqueArray = array.filter((item, index, list) => list.indexOf(item) === index);
+            const self = other;
+            let notUnique = array.filter((item, index) => self.indexOf(item) === index);
+            let shadowed = items.filter((item, index, array) => array.indexOf(item) === index);
+            let outer = items.filter((item, index, list) => array.indexOf(item) === index);";
+        let result = apply_to_source(&Duplicates, source_text);
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].line, 2);
+        assert_eq!(result[1].line, 5);
+    }
 }
 // endregion: tests
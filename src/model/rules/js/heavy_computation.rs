@@ -0,0 +1,452 @@
+use crate::model::ctx::Ctx;
+use crate::model::rules::LineResult;
+use crate::model::rules::Severity;
+
+use oxc::ast::ast::{
+    ArrowFunctionExpression, AssignmentExpression, CallExpression, DoWhileStatement, Expression,
+    ForInStatement, ForOfStatement, ForStatement, ForStatementInit, Function,
+    SimpleAssignmentTarget, WhileStatement,
+};
+use oxc::ast::visit::walk::{
+    walk_arrow_function_expression, walk_assignment_expression, walk_call_expression,
+    walk_do_while_statement, walk_for_in_statement, walk_for_of_statement, walk_for_statement,
+    walk_function, walk_while_statement,
+};
+use oxc::ast::Visit;
+use oxc::span::{GetSpan, Span};
+use oxc::syntax::operator::{AssignmentOperator, BinaryOperator, UpdateOperator};
+use oxc::syntax::scope::ScopeFlags;
+
+use super::{line_column, numeric_value, Rule};
+
+/// libraries whose functions hash, encrypt or compress synchronously on the calling thread
+const SYNC_HEAVY_LIBRARIES: [&str; 5] = ["CryptoJS", "pako", "LZString", "fflate", "bcrypt"];
+
+/// events whose `on<event>` property is a handler, `user.onboarding` or `state.online` are not
+const DOM_EVENTS: [&str; 44] = [
+    "abort",
+    "animationend",
+    "beforeunload",
+    "blur",
+    "change",
+    "click",
+    "contextmenu",
+    "copy",
+    "cut",
+    "dblclick",
+    "drag",
+    "dragend",
+    "dragover",
+    "drop",
+    "error",
+    "focus",
+    "hashchange",
+    "input",
+    "keydown",
+    "keypress",
+    "keyup",
+    "load",
+    "message",
+    "mousedown",
+    "mousemove",
+    "mouseout",
+    "mouseover",
+    "mouseup",
+    "paste",
+    "pointerdown",
+    "pointermove",
+    "pointerup",
+    "popstate",
+    "readystatechange",
+    "reset",
+    "resize",
+    "scroll",
+    "select",
+    "storage",
+    "submit",
+    "touchend",
+    "touchmove",
+    "touchstart",
+    "wheel",
+];
+
+/// a loop nest: start of the outermost loop, estimated iterations, deepest nesting
+type LoopNest = (u32, f64, usize);
+
+/// This rule is used to find heavy synchronous computation that blocks the main thread
+#[derive(Debug)]
+pub struct HeavyComputation {
+    /// loop nests estimated to run at least this many iterations are reported
+    pub iteration_threshold: f64,
+    /// loop nests at least this deep are reported, even if their bounds are unknown
+    pub max_nesting_depth: usize,
+}
+
+impl Default for HeavyComputation {
+    fn default() -> Self {
+        HeavyComputation {
+            iteration_threshold: 100_000.0,
+            max_nesting_depth: 3,
+        }
+    }
+}
+
+impl Rule for HeavyComputation {
+    fn get_name(&self) -> &str {
+        "JS-Heavy-Computation"
+    }
+    fn get_description(&self) -> &str {
+        "long synchronous work on the main thread makes the page stutter and keeps the CPU busy. move it to a Web Worker or split it into chunks with 'setTimeout' or 'requestIdleCallback'"
+    }
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        if let Ctx::JavaScriptCtx(js_ctx) = ctx {
+            let mut finder = ComputationFinder::default();
+            finder.visit_program(js_ctx.program);
+
+            let mut matches = vec![];
+            for (start, iterations, depth, in_handler) in &finder.loop_nests {
+                let heavy = *iterations >= self.iteration_threshold;
+                let deep = *depth >= self.max_nesting_depth;
+                if !heavy && !deep {
+                    continue;
+                }
+                let size = if heavy {
+                    format!("runs about {:.0} iterations", iterations)
+                } else {
+                    format!("nests {} loops", depth)
+                };
+                matches.push((*start, *in_handler, format!("this loop {}", size)));
+            }
+            for callee in &finder.library_calls {
+                let name = js_ctx
+                    .input
+                    .get(callee.start as usize..callee.end as usize)
+                    .unwrap_or_default();
+                matches.push((callee.start, true, format!("'{}' runs synchronously", name)));
+            }
+            matches.sort_by_key(|(start, _, _)| *start);
+
+            let mut result = vec![];
+            for (start, in_handler, description) in matches {
+                let (line, column) = line_column(js_ctx.input, start);
+                let (severity, place) = if in_handler {
+                    (Severity::Warning, " inside an event handler")
+                } else {
+                    (Severity::Info, "")
+                };
+                result.push(LineResult {
+                    severity,
+                    line,
+                    column,
+                    classification: self.get_name().to_string(),
+                    description: format!("{}{}. {}", description, place, self.get_description()),
+                });
+            }
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+/// Visitor that estimates the size of loop nests and finds heavy library calls in event handlers
+#[derive(Debug, Default)]
+struct ComputationFinder {
+    /// outermost loop start, estimated iterations, deepest nesting, inside an event handler
+    loop_nests: Vec<(u32, f64, usize, bool)>,
+    /// callee of library calls inside event handlers
+    library_calls: Vec<Span>,
+    /// estimated iterations of the loops around the current node within the current function
+    loops: Vec<Option<f64>>,
+    /// the loop nest the current node is in
+    nest: Option<LoopNest>,
+    /// loops and loop nest of the enclosing functions
+    saved: Vec<(Vec<Option<f64>>, Option<LoopNest>, bool)>,
+    /// whether the current function is an event handler
+    in_handler: bool,
+    /// set while visiting the arguments of `addEventListener` or the value of an `on...` property
+    handler_callback: bool,
+}
+
+impl<'a> Visit<'a> for ComputationFinder {
+    fn visit_call_expression(&mut self, expr: &CallExpression<'a>) {
+        // `pako.deflate(data)` or `CryptoJS.AES.encrypt(text, key)`
+        if self.in_handler {
+            let is_library_call = expr.callee.get_member_expr().is_some_and(|member| {
+                root_identifier(member.object())
+                    .is_some_and(|library| SYNC_HEAVY_LIBRARIES.contains(&library))
+            });
+            if is_library_call {
+                self.library_calls.push(expr.callee.span());
+            }
+        }
+        if expr.callee_name() == Some("addEventListener") {
+            self.visit_expression(&expr.callee);
+            self.handler_callback = true;
+            self.visit_arguments(&expr.arguments);
+            self.handler_callback = false;
+        } else {
+            walk_call_expression(self, expr);
+        }
+    }
+
+    fn visit_assignment_expression(&mut self, expr: &AssignmentExpression<'a>) {
+        // `button.onclick = () => { ... }`
+        let is_handler_property = expr.left.as_member_expression().is_some_and(|member| {
+            member
+                .static_property_name()
+                .and_then(|name| name.strip_prefix("on"))
+                .is_some_and(|event| DOM_EVENTS.contains(&event))
+        });
+        if is_handler_property {
+            self.visit_assignment_target(&expr.left);
+            self.handler_callback = true;
+            self.visit_expression(&expr.right);
+            self.handler_callback = false;
+        } else {
+            walk_assignment_expression(self, expr);
+        }
+    }
+
+    fn visit_function(&mut self, func: &Function<'a>, flags: ScopeFlags) {
+        self.enter_function();
+        walk_function(self, func, flags);
+        self.leave_function();
+    }
+
+    fn visit_arrow_function_expression(&mut self, expr: &ArrowFunctionExpression<'a>) {
+        self.enter_function();
+        walk_arrow_function_expression(self, expr);
+        self.leave_function();
+    }
+
+    fn visit_for_statement(&mut self, stmt: &ForStatement<'a>) {
+        self.enter_loop(for_iterations(stmt), stmt.span.start);
+        walk_for_statement(self, stmt);
+        self.leave_loop();
+    }
+
+    fn visit_for_of_statement(&mut self, stmt: &ForOfStatement<'a>) {
+        self.enter_loop(None, stmt.span.start);
+        walk_for_of_statement(self, stmt);
+        self.leave_loop();
+    }
+
+    fn visit_for_in_statement(&mut self, stmt: &ForInStatement<'a>) {
+        self.enter_loop(None, stmt.span.start);
+        walk_for_in_statement(self, stmt);
+        self.leave_loop();
+    }
+
+    fn visit_while_statement(&mut self, stmt: &WhileStatement<'a>) {
+        self.enter_loop(None, stmt.span.start);
+        walk_while_statement(self, stmt);
+        self.leave_loop();
+    }
+
+    fn visit_do_while_statement(&mut self, stmt: &DoWhileStatement<'a>) {
+        self.enter_loop(None, stmt.span.start);
+        walk_do_while_statement(self, stmt);
+        self.leave_loop();
+    }
+}
+
+impl ComputationFinder {
+    /// A function body starts outside of any loop and is an event handler if it was passed as one
+    fn enter_function(&mut self) {
+        let is_handler = std::mem::take(&mut self.handler_callback);
+        self.saved.push((
+            std::mem::take(&mut self.loops),
+            self.nest.take(),
+            self.in_handler,
+        ));
+        self.in_handler = is_handler;
+    }
+
+    fn leave_function(&mut self) {
+        if let Some((loops, nest, in_handler)) = self.saved.pop() {
+            self.loops = loops;
+            self.nest = nest;
+            self.in_handler = in_handler;
+        }
+    }
+
+    /// Multiply the known bounds of the enclosing loops, unknown bounds count as a single iteration
+    fn enter_loop(&mut self, iterations: Option<f64>, start: u32) {
+        self.loops.push(iterations);
+        let estimate: f64 = self
+            .loops
+            .iter()
+            .map(|bound| bound.unwrap_or(1.0))
+            .product();
+        let depth = self.loops.len();
+        self.nest = Some(match self.nest {
+            Some((nest_start, nest_estimate, nest_depth)) => (
+                nest_start,
+                nest_estimate.max(estimate),
+                nest_depth.max(depth),
+            ),
+            None => (start, estimate, depth),
+        });
+    }
+
+    /// The nest is complete when its outermost loop is left
+    fn leave_loop(&mut self) {
+        self.loops.pop();
+        if self.loops.is_empty() {
+            if let Some((start, estimate, depth)) = self.nest.take() {
+                self.loop_nests
+                    .push((start, estimate, depth, self.in_handler));
+            }
+        }
+    }
+}
+
+// region: helpers
+
+/// Estimate the iterations of a counting loop like `for (let i = 0; i < 100000; i++)`
+fn for_iterations(stmt: &ForStatement<'_>) -> Option<f64> {
+    let Some(Expression::BinaryExpression(test)) = &stmt.test else {
+        return None;
+    };
+    // the counter is compared with a literal bound, on either side
+    let (bound, operator) = match (numeric_value(&test.left), numeric_value(&test.right)) {
+        (None, Some(bound)) => (bound, test.operator),
+        (Some(bound), None) => (bound, flipped(test.operator)?),
+        _ => return None,
+    };
+    let initial = match &stmt.init {
+        Some(ForStatementInit::VariableDeclaration(declaration)) => declaration
+            .declarations
+            .first()
+            .and_then(|declarator| declarator.init.as_ref())
+            .and_then(numeric_value)?,
+        Some(init) => match init.as_expression()? {
+            Expression::AssignmentExpression(assignment) => numeric_value(&assignment.right)?,
+            _ => return None,
+        },
+        None => return None,
+    };
+    let step = match &stmt.update {
+        Some(Expression::UpdateExpression(update)) => match update.operator {
+            UpdateOperator::Increment | UpdateOperator::Decrement => 1.0,
+        },
+        Some(Expression::AssignmentExpression(assignment))
+            if matches!(
+                assignment.operator,
+                AssignmentOperator::Addition | AssignmentOperator::Subtraction
+            ) && matches!(
+                assignment.left.as_simple_assignment_target(),
+                Some(SimpleAssignmentTarget::AssignmentTargetIdentifier(_))
+            ) =>
+        {
+            numeric_value(&assignment.right)?.abs()
+        }
+        _ => return None,
+    };
+    let inclusive = match operator {
+        BinaryOperator::LessThan | BinaryOperator::GreaterThan => 0.0,
+        BinaryOperator::LessEqualThan | BinaryOperator::GreaterEqualThan => 1.0,
+        _ => return None,
+    };
+    if step == 0.0 {
+        return None;
+    }
+    Some(((bound - initial).abs() / step).ceil() + inclusive)
+}
+
+/// Get the operator with swapped operands, `100 > i` is `i < 100`
+fn flipped(operator: BinaryOperator) -> Option<BinaryOperator> {
+    match operator {
+        BinaryOperator::LessThan => Some(BinaryOperator::GreaterThan),
+        BinaryOperator::LessEqualThan => Some(BinaryOperator::GreaterEqualThan),
+        BinaryOperator::GreaterThan => Some(BinaryOperator::LessThan),
+        BinaryOperator::GreaterEqualThan => Some(BinaryOperator::LessEqualThan),
+        _ => None,
+    }
+}
+
+/// Get the identifier a member chain starts with, `CryptoJS` for `CryptoJS.AES`
+fn root_identifier<'e>(expr: &'e Expression<'_>) -> Option<&'e str> {
+    match expr.get_inner_expression() {
+        Expression::Identifier(identifier) => Some(identifier.name.as_str()),
+        expr => root_identifier(expr.get_member_expr()?.object()),
+    }
+}
+
+// endregion: helpers
+// region: tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::rules::js::apply_to_source;
+
+    #[test]
+    fn test_literal_bounds_are_estimated() {
+        let source_text = "let array = [];
+            for (let i = 0; i < 100000; i++) {
+                array.push(Math.floor(Math.random() * 100000));
+            }
+            for (let i = 0; i < 1000; i++) { total += i; }
+            for (let x = 0; x < 500; x++) {
+                for (let y = 500; y > 0; y--) { plot(x, y); }
+            }";
+        let result = apply_to_source(&HeavyComputation::default(), source_text);
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].line, 2);
+        assert!(matches!(result[0].severity, Severity::Info));
+        assert!(result[0]
+            .description
+            .starts_with("this loop runs about 100000 iterations."));
+        assert!(result[1]
+            .description
+            .starts_with("this loop runs about 250000 iterations."));
+    }
+
+    #[test]
+    fn test_heavy_work_in_event_handlers_is_a_warning() {
+        let source_text = "upload.addEventListener('change', (event) => {
+                const packed = pako.deflate(event.target.result);
+                for (let i = 0; i < 200000; i += 2) { checksum ^= packed[i]; }
+            });
+            button.onclick = function () { CryptoJS.AES.encrypt(text, key); };
+            const hash = CryptoJS.SHA256(config);";
+        let result = apply_to_source(&HeavyComputation::default(), source_text);
+        assert_eq!(result.len(), 3);
+        assert!(result
+            .iter()
+            .all(|line_result| matches!(line_result.severity, Severity::Warning)));
+        assert!(result[0]
+            .description
+            .starts_with("'pako.deflate' runs synchronously inside an event handler"));
+        assert!(result[1].description.contains("100000 iterations"));
+        assert!(result[2].description.starts_with("'CryptoJS.AES.encrypt'"));
+    }
+
+    #[test]
+    fn test_properties_starting_with_on_are_not_handlers() {
+        let source_text =
+            "user.onboarding = function () { for (let i = 0; i < 200000; i++) { step(i); } };
+            state.online = () => { for (let i = 0; i < 200000; i++) { sync(i); } };
+            form.onsubmit = () => { for (let i = 0; i < 200000; i++) { check(i); } };";
+        let result = apply_to_source(&HeavyComputation::default(), source_text);
+        assert_eq!(result.len(), 3);
+        assert!(matches!(result[0].severity, Severity::Info));
+        assert!(matches!(result[1].severity, Severity::Info));
+        assert!(matches!(result[2].severity, Severity::Warning));
+    }
+
+    #[test]
+    fn test_deep_nesting_with_unknown_bounds() {
+        let source_text = "for (const row of rows) {
+                for (const cell of row) {
+                    while (cell.next) { cell = cell.next; }
+                }
+            }
+            for (const row of rows) { for (const cell of row) { draw(cell); } }";
+        let result = apply_to_source(&HeavyComputation::default(), source_text);
+        assert_eq!(result.len(), 1);
+        assert!(result[0].description.starts_with("this loop nests 3 loops"));
+    }
+}
+// endregion: tests
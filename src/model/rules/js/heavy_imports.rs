@@ -0,0 +1,228 @@
+use crate::model::ctx::Ctx;
+use crate::model::rules::LineResult;
+use crate::model::rules::Severity;
+
+use oxc::ast::ast::{CallExpression, ImportDeclaration, ImportDeclarationSpecifier};
+use oxc::ast::visit::walk::walk_call_expression;
+use oxc::ast::Visit;
+
+use super::{line_column, Rule};
+
+/// known heavy packages: name, approximate minified + gzipped size in KB, tree-shakeable, alternative
+const DEFAULT_HEAVY_PACKAGES: [(&str, u32, bool, &str); 8] = [
+    (
+        "lodash",
+        25,
+        false,
+        "per-method imports like 'lodash/debounce' or 'lodash-es'",
+    ),
+    ("underscore", 7, false, "native array and object methods"),
+    (
+        "moment",
+        72,
+        false,
+        "'date-fns', 'dayjs' or the native 'Intl.DateTimeFormat'",
+    ),
+    (
+        "jquery",
+        30,
+        false,
+        "native DOM APIs like 'querySelector', 'classList' and 'fetch'",
+    ),
+    ("axios", 14, false, "the native 'fetch' API"),
+    ("bluebird", 18, false, "native Promises"),
+    (
+        "rxjs",
+        35,
+        true,
+        "named imports of only the operators you use, e.g. 'import { map } from \"rxjs\"'",
+    ),
+    (
+        "core-js",
+        60,
+        true,
+        "targeted polyfills for the browsers you support, e.g. 'core-js/actual/array/flat'",
+    ),
+];
+
+/// a package that adds a lot to the bundle when it is imported as a whole
+#[derive(Debug, Clone)]
+pub struct HeavyPackage {
+    /// package name as used in the import source
+    pub name: String,
+    /// approximate minified + gzipped size in kilobytes
+    pub size_kb: u32,
+    /// whether bundlers can drop the unused parts of named imports
+    pub tree_shakeable: bool,
+    /// lighter alternative or per-method import path
+    pub alternative: String,
+}
+
+/// This rule is used to find whole-library imports of heavy packages
+#[derive(Debug)]
+pub struct HeavyImports {
+    /// table of heavy packages the imports are checked against
+    pub packages: Vec<HeavyPackage>,
+    /// import source, whether the whole library is imported, start
+    imports: Vec<(String, bool, u32)>,
+}
+
+impl Default for HeavyImports {
+    fn default() -> Self {
+        let packages = DEFAULT_HEAVY_PACKAGES
+            .iter()
+            .map(
+                |(name, size_kb, tree_shakeable, alternative)| HeavyPackage {
+                    name: name.to_string(),
+                    size_kb: *size_kb,
+                    tree_shakeable: *tree_shakeable,
+                    alternative: alternative.to_string(),
+                },
+            )
+            .collect();
+        HeavyImports {
+            packages,
+            imports: vec![],
+        }
+    }
+}
+
+impl Rule for HeavyImports {
+    fn get_name(&self) -> &str {
+        "JS-Heavy-Imports"
+    }
+    fn get_description(&self) -> &str {
+        "whole-library imports of heavy packages bloat the bundle every visitor has to download and parse. import single methods or use a lighter alternative"
+    }
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        if let Ctx::JavaScriptCtx(js_ctx) = ctx {
+            let mut heavy_imports = HeavyImports {
+                packages: vec![],
+                imports: vec![],
+            };
+            heavy_imports.visit_program(js_ctx.program);
+
+            let mut result = vec![];
+            for (source, whole_library, start) in &heavy_imports.imports {
+                let Some(package) = self.packages.iter().find(|package| package.name == *source)
+                else {
+                    continue;
+                };
+                // named imports are fine as long as the bundler can drop the rest
+                if !whole_library && package.tree_shakeable {
+                    continue;
+                }
+                let problem = if *whole_library {
+                    "is imported as a whole and adds"
+                } else {
+                    "cannot be tree-shaken, so importing from it adds"
+                };
+                let (line, column) = line_column(js_ctx.input, *start);
+                result.push(LineResult {
+                    severity: Severity::Warning,
+                    line,
+                    column,
+                    classification: self.get_name().to_string(),
+                    description: format!(
+                        "'{}' {} about {} KB (minified + gzipped) to the bundle. consider {}. {}",
+                        package.name,
+                        problem,
+                        package.size_kb,
+                        package.alternative,
+                        self.get_description()
+                    ),
+                });
+            }
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a> Visit<'a> for HeavyImports {
+    fn visit_import_declaration(&mut self, decl: &ImportDeclaration<'a>) {
+        // `import 'lodash'`, `import _ from 'lodash'` and `import * as _ from 'lodash'` load the whole library
+        let whole_library = match &decl.specifiers {
+            None => true,
+            Some(specifiers) => specifiers.iter().any(|specifier| {
+                matches!(
+                    specifier,
+                    ImportDeclarationSpecifier::ImportDefaultSpecifier(_)
+                        | ImportDeclarationSpecifier::ImportNamespaceSpecifier(_)
+                )
+            }),
+        };
+        self.imports.push((
+            decl.source.value.to_string(),
+            whole_library,
+            decl.span.start,
+        ));
+    }
+
+    fn visit_call_expression(&mut self, expr: &CallExpression<'a>) {
+        // `require('rxjs')` always evaluates the whole module
+        if let Some(source) = expr.common_js_require() {
+            self.imports
+                .push((source.value.to_string(), true, expr.span.start));
+        }
+        walk_call_expression(self, expr);
+    }
+}
+
+// region: tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::rules::js::apply_to_source;
+
+    #[test]
+    fn test_whole_library_imports_are_reported() {
+        let source_text = "import _ from 'lodash';
+            import * as moment from 'moment';
+            const rx = require('rxjs');";
+        let result = apply_to_source(&HeavyImports::default(), source_text);
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[2].line, 3);
+        assert!(result[1]
+            .description
+            .starts_with("'moment' is imported as a whole and adds about 72 KB"));
+    }
+
+    #[test]
+    fn test_per_method_and_tree_shakeable_imports_are_ignored() {
+        let source_text = "import debounce from 'lodash/debounce';
+            import { map, filter } from 'rxjs';
+            import { format } from 'date-fns';";
+        let result = apply_to_source(&HeavyImports::default(), source_text);
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_named_imports_from_non_tree_shakeable_package_are_reported() {
+        let source_text = "import { debounce } from 'lodash';";
+        let result = apply_to_source(&HeavyImports::default(), source_text);
+        assert_eq!(result.len(), 1);
+        assert!(result[0]
+            .description
+            .starts_with("'lodash' cannot be tree-shaken, so importing from it adds about 25 KB"));
+    }
+
+    #[test]
+    fn test_package_table_is_configurable() {
+        let rule = HeavyImports {
+            packages: vec![HeavyPackage {
+                name: "chart.js".to_string(),
+                size_kb: 60,
+                tree_shakeable: true,
+                alternative: "registering only the chart types you use".to_string(),
+            }],
+            ..HeavyImports::default()
+        };
+        let source_text = "import Chart from 'chart.js'; import _ from 'lodash';";
+        let result = apply_to_source(&rule, source_text);
+        assert_eq!(result.len(), 1);
+        assert!(result[0].description.starts_with("'chart.js'"));
+    }
+}
+// endregion: tests
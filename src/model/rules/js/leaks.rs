@@ -0,0 +1,331 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::model::ctx::Ctx;
+use crate::model::rules::LineResult;
+use crate::model::rules::Severity;
+
+use oxc::ast::ast::{
+    Argument, AssignmentExpression, AssignmentTarget, CallExpression, Expression,
+    ObjectPropertyKind, VariableDeclarator,
+};
+use oxc::ast::visit::walk::{
+    walk_assignment_expression, walk_call_expression, walk_variable_declarator,
+};
+use oxc::ast::Visit;
+use oxc::semantic::{Semantic, SymbolId};
+use oxc::span::{GetSpan, Span};
+
+use super::{line_column, resolve_symbol, Rule};
+
+/// what a listener handler or interval handle refers to
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Handle {
+    /// a variable or function declared in the program
+    Symbol(SymbolId),
+    /// a global or a property like `this.timer`, compared by its source text
+    Name(String),
+}
+
+/// This rule is used to find event listeners that are never removed and intervals that are never cleared
+pub struct Leaks;
+
+impl Rule for Leaks {
+    fn get_name(&self) -> &str {
+        "JS-Listener-Timer-Leaks"
+    }
+    fn get_description(&self) -> &str {
+        "listeners and intervals that are never removed keep their objects in memory and their work running for the whole lifetime of the page"
+    }
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        if let Ctx::JavaScriptCtx(js_ctx) = ctx {
+            let mut finder = LeakFinder {
+                semantic: js_ctx.semantic,
+                input: js_ctx.input,
+                added_listeners: vec![],
+                removed_listeners: HashSet::new(),
+                intervals: vec![],
+                stored_intervals: HashMap::new(),
+                cleared_intervals: HashSet::new(),
+            };
+            finder.visit_program(js_ctx.program);
+
+            let mut matches = vec![];
+            for (target, event, handler, start) in &finder.added_listeners {
+                let removed = handler.as_ref().is_some_and(|handler| {
+                    finder.removed_listeners.contains(&(
+                        target.clone(),
+                        event.clone(),
+                        handler.clone(),
+                    ))
+                });
+                if !removed {
+                    matches.push((*start, listener_description(event, handler.is_none())));
+                }
+            }
+            for (handle, start) in &finder.intervals {
+                let cleared = handle
+                    .as_ref()
+                    .is_some_and(|handle| finder.cleared_intervals.contains(handle));
+                if !cleared {
+                    matches.push((*start, interval_description(handle.is_none())));
+                }
+            }
+            matches.sort_by_key(|(start, _)| *start);
+
+            let mut result = vec![];
+            for (start, description) in matches {
+                let (line, column) = line_column(js_ctx.input, start);
+                result.push(LineResult {
+                    severity: Severity::Info,
+                    line,
+                    column,
+                    classification: self.get_name().to_string(),
+                    description,
+                });
+            }
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+/// Visitor that pairs add/remove listener and set/clear interval calls using the semantic model
+struct LeakFinder<'s, 'a> {
+    semantic: &'s Semantic<'a>,
+    input: &'s str,
+    /// target, event, handler (`None` for inline functions), start
+    added_listeners: Vec<(String, String, Option<Handle>, u32)>,
+    /// target, event, handler
+    removed_listeners: HashSet<(String, String, Handle)>,
+    /// handle the interval is stored in, start
+    intervals: Vec<(Option<Handle>, u32)>,
+    /// start of setInterval calls whose result is stored, with the handle it is stored in
+    stored_intervals: HashMap<u32, Handle>,
+    /// handles passed to clearInterval or clearTimeout
+    cleared_intervals: HashSet<Handle>,
+}
+
+impl<'a> Visit<'a> for LeakFinder<'_, 'a> {
+    fn visit_call_expression(&mut self, expr: &CallExpression<'a>) {
+        match expr.callee_name() {
+            Some("addEventListener") => {
+                if let Some(event) = event_name(expr) {
+                    if !is_self_removing(expr.arguments.get(2)) {
+                        let handler = expr
+                            .arguments
+                            .get(1)
+                            .and_then(Argument::as_expression)
+                            .and_then(|handler| self.handle(handler));
+                        self.added_listeners.push((
+                            self.listener_target(expr),
+                            event,
+                            handler,
+                            expr.span.start,
+                        ));
+                    }
+                }
+            }
+            Some("removeEventListener") => {
+                let handler = expr
+                    .arguments
+                    .get(1)
+                    .and_then(Argument::as_expression)
+                    .and_then(|handler| self.handle(handler));
+                if let (Some(event), Some(handler)) = (event_name(expr), handler) {
+                    self.removed_listeners
+                        .insert((self.listener_target(expr), event, handler));
+                }
+            }
+            Some("setInterval") => {
+                let handle = self.stored_intervals.get(&expr.span.start).cloned();
+                self.intervals.push((handle, expr.span.start));
+            }
+            Some("clearInterval" | "clearTimeout") => {
+                if let Some(handle) = expr
+                    .arguments
+                    .first()
+                    .and_then(Argument::as_expression)
+                    .and_then(|handle| self.handle(handle))
+                {
+                    self.cleared_intervals.insert(handle);
+                }
+            }
+            _ => {}
+        }
+        walk_call_expression(self, expr);
+    }
+
+    fn visit_variable_declarator(&mut self, declarator: &VariableDeclarator<'a>) {
+        // `const timer = setInterval(tick, 1000)`
+        if let (Some(start), Some(identifier)) = (
+            interval_call_start(declarator.init.as_ref()),
+            declarator.id.get_binding_identifier(),
+        ) {
+            if let Some(symbol_id) = identifier.symbol_id.get() {
+                self.stored_intervals
+                    .insert(start, Handle::Symbol(symbol_id));
+            }
+        }
+        walk_variable_declarator(self, declarator);
+    }
+
+    fn visit_assignment_expression(&mut self, expr: &AssignmentExpression<'a>) {
+        // `timer = setInterval(tick, 1000)` or `this.timer = setInterval(tick, 1000)`
+        if let Some(start) = interval_call_start(Some(&expr.right)) {
+            let handle = match &expr.left {
+                AssignmentTarget::AssignmentTargetIdentifier(identifier) => {
+                    Some(match resolve_symbol(self.semantic, identifier) {
+                        Some(symbol_id) => Handle::Symbol(symbol_id),
+                        None => Handle::Name(identifier.name.to_string()),
+                    })
+                }
+                target => target
+                    .as_member_expression()
+                    .map(|member| Handle::Name(self.source_text(member.span()))),
+            };
+            if let Some(handle) = handle {
+                self.stored_intervals.insert(start, handle);
+            }
+        }
+        walk_assignment_expression(self, expr);
+    }
+}
+
+impl LeakFinder<'_, '_> {
+    /// Resolve a handler or interval handle, `None` for inline functions and other expressions
+    fn handle(&self, expr: &Expression<'_>) -> Option<Handle> {
+        match expr.get_inner_expression() {
+            Expression::Identifier(identifier) => {
+                Some(match resolve_symbol(self.semantic, identifier) {
+                    Some(symbol_id) => Handle::Symbol(symbol_id),
+                    None => Handle::Name(identifier.name.to_string()),
+                })
+            }
+            expr => expr
+                .get_member_expr()
+                .map(|member| Handle::Name(self.source_text(member.span()))),
+        }
+    }
+
+    /// Get the source text of the object the listener is added to, a bare `addEventListener` call adds it to `window`
+    fn listener_target(&self, expr: &CallExpression<'_>) -> String {
+        match expr.callee.get_member_expr() {
+            Some(member) => self.source_text(member.object().span()),
+            None => "window".to_string(),
+        }
+    }
+
+    fn source_text(&self, span: Span) -> String {
+        self.input
+            .get(span.start as usize..span.end as usize)
+            .unwrap_or_default()
+            .to_string()
+    }
+}
+
+// region: helpers
+
+fn listener_description(event: &str, inline_handler: bool) -> String {
+    if inline_handler {
+        format!("'{}' listener uses an inline handler that can never be removed. store the handler in a variable to remove it later, or pass '{{ once: true }}' or an AbortController 'signal'", event)
+    } else {
+        format!("'{}' listener is never removed. call 'removeEventListener' with the same handler when the element or component is destroyed, or pass '{{ once: true }}' or an AbortController 'signal'", event)
+    }
+}
+
+fn interval_description(handle_discarded: bool) -> String {
+    if handle_discarded {
+        "the handle returned by 'setInterval' is not stored, so the interval can never be cleared. store it and call 'clearInterval' when it is no longer needed".to_string()
+    } else {
+        "'setInterval' is never cleared and keeps running until the page is closed. call 'clearInterval' with its handle when it is no longer needed".to_string()
+    }
+}
+
+/// Get the event name of an add/removeEventListener call
+fn event_name(expr: &CallExpression<'_>) -> Option<String> {
+    match expr.arguments.first()? {
+        Argument::StringLiteral(event) => Some(event.value.to_string()),
+        _ => None,
+    }
+}
+
+/// Check if the listener options remove the listener automatically: `{ once: true }` or `{ signal }`
+fn is_self_removing(options: Option<&Argument<'_>>) -> bool {
+    let Some(Expression::ObjectExpression(options)) = options.and_then(Argument::as_expression)
+    else {
+        return false;
+    };
+    options.properties.iter().any(|property| {
+        matches!(property, ObjectPropertyKind::ObjectProperty(property)
+            if property.key.is_specific_static_name("signal")
+                || (property.key.is_specific_static_name("once")
+                    && property.value.get_boolean_value() == Some(true)))
+    })
+}
+
+/// Get the start of the expression if it is a setInterval call
+fn interval_call_start(expr: Option<&Expression<'_>>) -> Option<u32> {
+    match expr?.get_inner_expression() {
+        Expression::CallExpression(call) if call.callee_name() == Some("setInterval") => {
+            Some(call.span.start)
+        }
+        _ => None,
+    }
+}
+
+// endregion: helpers
+// region: tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::rules::js::apply_to_source;
+
+    #[test]
+    fn test_paired_listeners_and_intervals_are_ignored() {
+        let source_text = "function onResize() { layout(); }
+            window.addEventListener('resize', onResize);
+            window.removeEventListener('resize', onResize);
+            const timer = setInterval(tick, 1000);
+            clearInterval(timer);
+            this.poller = setInterval(poll, 5000);
+            clearInterval(this.poller);
+            button.addEventListener('click', () => save(), { once: true });";
+        let result = apply_to_source(&Leaks, source_text);
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_unpaired_listeners_and_intervals_are_reported() {
+        let source_text = "document.addEventListener('keydown', (event) => handle(event));
+            setInterval(tick, 1000);
+            let clock = setInterval(tick, 1000);";
+        let result = apply_to_source(&Leaks, source_text);
+        assert_eq!(result.len(), 3);
+        assert!(result[0].description.contains("inline handler"));
+        assert!(result[1].description.contains("not stored"));
+        assert!(result[2].description.contains("never cleared"));
+    }
+
+    #[test]
+    fn test_handlers_are_paired_by_target_and_binding_not_by_name() {
+        let source_text = "function mount() {
+                const onScroll = () => update();
+                window.addEventListener('scroll', onScroll);
+            }
+            function unmount() {
+                const onScroll = () => update();
+                window.removeEventListener('scroll', onScroll);
+            }";
+        let result = apply_to_source(&Leaks, source_text);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].line, 3);
+
+        let source_text = "window.addEventListener('resize', onResize);
+            panel.removeEventListener('resize', onResize);";
+        let result = apply_to_source(&Leaks, source_text);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].line, 1);
+    }
+}
+// endregion: tests
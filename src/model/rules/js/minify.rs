@@ -16,7 +16,7 @@ impl Rule for Minify {
     fn apply(&self, ctx: &Ctx<'_>) -> Option<std::vec::Vec<LineResult>> {
         if let Ctx::JavaScriptCtx(js_ctx) = ctx {
             let minified = minify(js_ctx.input);
-            if minified.to_string() != js_ctx.input.to_string() {
+            if minified.to_string() != js_ctx.input {
                 Some(vec![LineResult {
                     severity: crate::model::rules::Severity::Info,
                     line: 0,
@@ -1,5 +1,130 @@
+pub mod array_chains;
+pub mod autoplay_media;
+pub mod blocking_apis;
+pub mod console_logging;
+pub mod dead_code;
 pub mod duplicates;
+pub mod heavy_computation;
+pub mod heavy_imports;
+pub mod leaks;
 pub mod minify;
+pub mod polling;
+pub mod sequential_await;
+pub mod timer_animation;
+pub mod unbounded_caches;
+pub mod unthrottled_listeners;
+pub mod wasteful_idioms;
 // pub mod template_rule;
 
+pub use super::line_column;
 use super::Rule;
+use std::collections::HashSet;
+
+use oxc::ast::ast::{CallExpression, Expression, IdentifierReference};
+use oxc::ast::visit::walk::walk_call_expression;
+use oxc::ast::Visit;
+use oxc::semantic::{Semantic, SymbolId};
+use oxc::syntax::operator::BinaryOperator;
+
+// region: helpers
+
+/// Get the symbol the identifier refers to, `None` for globals and undeclared variables
+pub fn resolve_symbol(
+    semantic: &Semantic<'_>,
+    identifier: &IdentifierReference<'_>,
+) -> Option<SymbolId> {
+    let reference_id = identifier.reference_id()?;
+    semantic.symbols().get_reference(reference_id).symbol_id()
+}
+
+/// Evaluate a numeric literal or simple arithmetic on numeric literals like `5 * 1000` or `1000 / 60`
+pub fn numeric_value(expression: &Expression<'_>) -> Option<f64> {
+    match expression.get_inner_expression() {
+        Expression::NumericLiteral(literal) => Some(literal.value),
+        Expression::BinaryExpression(binary) => {
+            let (left, right) = (numeric_value(&binary.left)?, numeric_value(&binary.right)?);
+            match binary.operator {
+                BinaryOperator::Multiplication => Some(left * right),
+                BinaryOperator::Division => Some(left / right),
+                BinaryOperator::Addition => Some(left + right),
+                BinaryOperator::Subtraction => Some(left - right),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Check whether the expression (or any function nested in it) calls one of the given functions
+/// both plain calls `throttle(fn)` and method calls `_.throttle(fn)` are matched by name
+pub fn calls_any(expression: &Expression<'_>, names: &[&str]) -> bool {
+    let mut finder = CallFinder {
+        names,
+        found: false,
+    };
+    finder.visit_expression(expression);
+    finder.found
+}
+
+/// Visitor that records whether one of the given functions is called in the visited subtree
+pub struct CallFinder<'n> {
+    pub names: &'n [&'n str],
+    pub found: bool,
+}
+
+impl<'a> Visit<'a> for CallFinder<'_> {
+    fn visit_call_expression(&mut self, expr: &CallExpression<'a>) {
+        if expr
+            .callee_name()
+            .is_some_and(|name| self.names.contains(&name))
+        {
+            self.found = true;
+        }
+        walk_call_expression(self, expr);
+    }
+}
+
+/// Collect the names of all identifiers referenced in the expression
+pub fn referenced_identifiers(expression: &Expression<'_>) -> HashSet<String> {
+    let mut collector = IdentifierCollector::default();
+    collector.visit_expression(expression);
+    collector.identifiers
+}
+
+/// Visitor that collects the names of the referenced identifiers in the visited subtree
+#[derive(Debug, Default)]
+pub struct IdentifierCollector {
+    pub identifiers: HashSet<String>,
+}
+
+impl<'a> Visit<'a> for IdentifierCollector {
+    fn visit_identifier_reference(&mut self, identifier: &IdentifierReference<'a>) {
+        self.identifiers.insert(identifier.name.to_string());
+    }
+}
+
+// endregion: helpers
+
+/// Parse the source text and apply the rule to it, used by the rule tests
+#[cfg(test)]
+pub fn apply_to_source(rule: &dyn Rule, source_text: &str) -> Vec<super::LineResult> {
+    use crate::model::ctx::{Ctx, JavaScriptCtx};
+    use oxc::allocator::Allocator;
+    use oxc::parser::Parser;
+    use oxc::semantic::SemanticBuilder;
+    use oxc::span::SourceType;
+
+    let allocator = Allocator::default();
+    let source_type = SourceType::from_path("javscript.js").unwrap();
+    let ret = Parser::new(&allocator, source_text, source_type).parse();
+    let semantic = SemanticBuilder::new(source_text, source_type)
+        .with_cfg(true)
+        .build(&ret.program)
+        .semantic;
+    let ctx = Ctx::JavaScriptCtx(JavaScriptCtx {
+        input: source_text,
+        program: &ret.program,
+        semantic: &semantic,
+    });
+    rule.apply(&ctx).unwrap_or_default()
+}
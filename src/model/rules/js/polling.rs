@@ -0,0 +1,261 @@
+use std::collections::HashSet;
+
+use crate::model::ctx::Ctx;
+use crate::model::rules::LineResult;
+use crate::model::rules::Severity;
+
+use oxc::ast::ast::{CallExpression, Expression, Function, VariableDeclarator};
+use oxc::ast::visit::walk::{walk_call_expression, walk_function, walk_variable_declarator};
+use oxc::ast::Visit;
+use oxc::syntax::scope::ScopeFlags;
+
+use super::{calls_any, line_column, numeric_value, CallFinder, Rule};
+
+/// calls that indicate a network request, `send` covers `XMLHttpRequest.send`
+const NETWORK_CALLS: [&str; 4] = ["fetch", "ajax", "getJSON", "send"];
+
+/// a timer that fires repeatedly at a short interval
+#[derive(Debug)]
+struct Timer {
+    /// setInterval or setTimeout
+    name: String,
+    /// delay in milliseconds
+    delay: f64,
+    start: u32,
+    /// name of the callback if it is passed by name
+    callback: Option<String>,
+    /// whether an inline callback performs a network request
+    network: bool,
+}
+
+/// This rule is used to find setInterval and recursive setTimeout polling with short intervals
+#[derive(Debug)]
+pub struct Polling {
+    /// timers firing more often than this (in milliseconds) are reported
+    pub min_interval_ms: f64,
+    matches: Vec<Timer>,
+    /// names of the functions the visitor is currently in, used to find recursive setTimeout calls
+    enclosing_functions: Vec<String>,
+    /// names of functions that perform a network request
+    network_functions: HashSet<String>,
+}
+
+impl Default for Polling {
+    fn default() -> Self {
+        Polling {
+            min_interval_ms: 10_000.0,
+            matches: vec![],
+            enclosing_functions: vec![],
+            network_functions: HashSet::new(),
+        }
+    }
+}
+
+impl Rule for Polling {
+    fn get_name(&self) -> &str {
+        "JS-Polling"
+    }
+    fn get_description(&self) -> &str {
+        "short-interval polling prevents the device from idling. prefer push mechanisms (WebSocket, Server-Sent Events), pause the timer on 'visibilitychange' while the page is hidden or back off exponentially"
+    }
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        if let Ctx::JavaScriptCtx(js_ctx) = ctx {
+            let mut polling = Polling {
+                min_interval_ms: self.min_interval_ms,
+                ..Polling::default()
+            };
+            polling.visit_program(js_ctx.program);
+
+            let mut result = vec![];
+            for timer in &polling.matches {
+                let network = timer.network
+                    || timer
+                        .callback
+                        .as_ref()
+                        .is_some_and(|callback| polling.network_functions.contains(callback));
+                let (line, column) = line_column(js_ctx.input, timer.start);
+                let (severity, action) = if network {
+                    (Severity::Warning, "polls the network")
+                } else {
+                    (Severity::Info, "runs")
+                };
+                result.push(LineResult {
+                    severity,
+                    line,
+                    column,
+                    classification: self.get_name().to_string(),
+                    description: format!(
+                        "'{}' {} every {:.0} ms. {}",
+                        timer.name,
+                        action,
+                        timer.delay,
+                        self.get_description()
+                    ),
+                });
+            }
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a> Visit<'a> for Polling {
+    fn visit_call_expression(&mut self, expr: &CallExpression<'a>) {
+        if let Some(name @ ("setInterval" | "setTimeout")) = expr.callee_name() {
+            if let Some(timer) = self.short_timer(name, expr) {
+                self.matches.push(timer);
+            }
+        }
+        // continue walking the AST
+        walk_call_expression(self, expr);
+    }
+
+    fn visit_function(&mut self, func: &Function<'a>, flags: ScopeFlags) {
+        match &func.id {
+            Some(id) => {
+                self.record_network_function(&id.name, func);
+                self.enclosing_functions.push(id.name.to_string());
+                walk_function(self, func, flags);
+                self.enclosing_functions.pop();
+            }
+            None => walk_function(self, func, flags),
+        }
+    }
+
+    fn visit_variable_declarator(&mut self, declarator: &VariableDeclarator<'a>) {
+        // `const poll = () => { ... }` is treated like a function declaration named poll
+        let function_name = match (&declarator.init, declarator.id.get_identifier()) {
+            (
+                Some(
+                    init @ (Expression::ArrowFunctionExpression(_)
+                    | Expression::FunctionExpression(_)),
+                ),
+                Some(name),
+            ) => {
+                if calls_any(init, &NETWORK_CALLS) {
+                    self.network_functions.insert(name.to_string());
+                }
+                Some(name.to_string())
+            }
+            _ => None,
+        };
+        match function_name {
+            Some(name) => {
+                self.enclosing_functions.push(name);
+                walk_variable_declarator(self, declarator);
+                self.enclosing_functions.pop();
+            }
+            None => walk_variable_declarator(self, declarator),
+        }
+    }
+}
+
+impl Polling {
+    /// Build the timer if it fires more often than the threshold
+    /// setTimeout is only considered polling if it schedules the function it is called from
+    fn short_timer(&self, name: &str, expr: &CallExpression<'_>) -> Option<Timer> {
+        let callback = expr.arguments.first()?.as_expression()?;
+        // a missing delay means the timer fires as soon as possible
+        let delay = match expr.arguments.get(1) {
+            Some(delay) => numeric_value(delay.as_expression()?)?,
+            None => 0.0,
+        };
+        if delay >= self.min_interval_ms {
+            return None;
+        }
+        let callback_name = match callback {
+            Expression::Identifier(identifier) => Some(identifier.name.to_string()),
+            _ => None,
+        };
+        if name == "setTimeout" {
+            let recursive = match &callback_name {
+                Some(callback_name) => self.enclosing_functions.contains(callback_name),
+                None => {
+                    let enclosing: Vec<&str> = self
+                        .enclosing_functions
+                        .iter()
+                        .map(String::as_str)
+                        .collect();
+                    calls_any(callback, &enclosing)
+                }
+            };
+            if !recursive {
+                return None;
+            }
+        }
+        Some(Timer {
+            name: name.to_string(),
+            delay,
+            start: expr.span.start,
+            network: callback_name.is_none() && calls_any(callback, &NETWORK_CALLS),
+            callback: callback_name,
+        })
+    }
+
+    /// Remember the function if its body performs a network request
+    fn record_network_function(&mut self, name: &str, func: &Function<'_>) {
+        if let Some(body) = &func.body {
+            let mut finder = CallFinder {
+                names: &NETWORK_CALLS,
+                found: false,
+            };
+            finder.visit_function_body(body);
+            if finder.found {
+                self.network_functions.insert(name.to_string());
+            }
+        }
+    }
+}
+
+// region: tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::rules::js::apply_to_source;
+
+    #[test]
+    fn test_short_interval_fetch_is_reported_as_warning() {
+        let source_text = "setInterval(() => fetch('/api/status').then(render), 1000);";
+        let result = apply_to_source(&Polling::default(), source_text);
+        assert_eq!(result.len(), 1);
+        assert!(matches!(result[0].severity, Severity::Warning));
+        assert!(result[0].description.contains("every 1000 ms"));
+    }
+
+    #[test]
+    fn test_recursive_set_timeout_is_reported() {
+        let source_text = "function poll() {
+                fetch('/api/messages').then(show);
+                setTimeout(poll, 2 * 1000);
+            }
+            poll();";
+        let result = apply_to_source(&Polling::default(), source_text);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].line, 3);
+        assert!(matches!(result[0].severity, Severity::Warning));
+    }
+
+    #[test]
+    fn test_one_off_timeouts_and_long_intervals_are_ignored() {
+        let source_text = "setTimeout(() => fetch('/api/once'), 500);
+            setInterval(refreshToken, 15 * 60 * 1000);
+            setInterval(updateClock, delay);";
+        let result = apply_to_source(&Polling::default(), source_text);
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_threshold_is_configurable() {
+        let source_text = "setInterval(updateClock, 1000);";
+        let rule = Polling {
+            min_interval_ms: 500.0,
+            ..Polling::default()
+        };
+        assert!(apply_to_source(&rule, source_text).is_empty());
+        let result = apply_to_source(&Polling::default(), source_text);
+        assert_eq!(result.len(), 1);
+        assert!(matches!(result[0].severity, Severity::Info));
+    }
+}
+// endregion: tests
@@ -0,0 +1,303 @@
+use std::collections::HashSet;
+
+use crate::model::ctx::Ctx;
+use crate::model::rules::LineResult;
+use crate::model::rules::Severity;
+
+use oxc::ast::ast::{
+    ArrowFunctionExpression, AssignmentExpression, AssignmentTarget, AwaitExpression,
+    BreakStatement, DoWhileStatement, Expression, ForInStatement, ForOfStatement, ForStatement,
+    Function, ReturnStatement, SimpleAssignmentTarget, Statement, SwitchStatement,
+    UpdateExpression, WhileStatement,
+};
+use oxc::ast::visit::walk::{
+    walk_assignment_expression, walk_await_expression, walk_do_while_statement,
+    walk_for_in_statement, walk_for_of_statement, walk_for_statement, walk_switch_statement,
+    walk_update_expression, walk_while_statement,
+};
+use oxc::ast::Visit;
+use oxc::span::Span;
+use oxc::syntax::scope::ScopeFlags;
+
+use super::{line_column, referenced_identifiers, Rule};
+
+/// This rule is used to find loops that await independent work one iteration after another
+#[derive(Debug, Default)]
+pub struct SequentialAwait {
+    /// number of awaits, loop start, start of the first await
+    matches: Vec<(usize, u32, u32)>,
+}
+
+impl Rule for SequentialAwait {
+    fn get_name(&self) -> &str {
+        "JS-Sequential-Await"
+    }
+    fn get_description(&self) -> &str {
+        "awaiting inside the loop runs the iterations one after another and keeps the radio and CPU awake longer. start all requests first and await them together with 'Promise.all' (e.g. 'await Promise.all(items.map(fetchItem))')"
+    }
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        if let Ctx::JavaScriptCtx(js_ctx) = ctx {
+            let mut sequential_await = SequentialAwait::default();
+            sequential_await.visit_program(js_ctx.program);
+
+            let mut result = vec![];
+            for (count, loop_start, await_start) in &sequential_await.matches {
+                let (line, column) = line_column(js_ctx.input, *loop_start);
+                let (await_line, _) = line_column(js_ctx.input, *await_start);
+                result.push(LineResult {
+                    severity: Severity::Warning,
+                    line,
+                    column,
+                    classification: self.get_name().to_string(),
+                    description: format!(
+                        "{} sequential 'await' in this loop (first at line {}). {}",
+                        count,
+                        await_line,
+                        self.get_description()
+                    ),
+                });
+            }
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a> Visit<'a> for SequentialAwait {
+    fn visit_for_statement(&mut self, stmt: &ForStatement<'a>) {
+        self.check_loop(stmt.span, &stmt.body, stmt.test.as_ref());
+        walk_for_statement(self, stmt);
+    }
+
+    fn visit_for_of_statement(&mut self, stmt: &ForOfStatement<'a>) {
+        // `for await (...)` consumes an async iterator and is sequential on purpose
+        if !stmt.r#await {
+            self.check_loop(stmt.span, &stmt.body, None);
+        }
+        walk_for_of_statement(self, stmt);
+    }
+
+    fn visit_for_in_statement(&mut self, stmt: &ForInStatement<'a>) {
+        self.check_loop(stmt.span, &stmt.body, None);
+        walk_for_in_statement(self, stmt);
+    }
+
+    fn visit_while_statement(&mut self, stmt: &WhileStatement<'a>) {
+        self.check_loop(stmt.span, &stmt.body, Some(&stmt.test));
+        walk_while_statement(self, stmt);
+    }
+
+    fn visit_do_while_statement(&mut self, stmt: &DoWhileStatement<'a>) {
+        self.check_loop(stmt.span, &stmt.body, Some(&stmt.test));
+        walk_do_while_statement(self, stmt);
+    }
+}
+
+impl SequentialAwait {
+    /// Record the loop if its body awaits and the iterations do not depend on each other
+    fn check_loop(&mut self, span: Span, body: &Statement<'_>, test: Option<&Expression<'_>>) {
+        let mut scan = LoopScan::default();
+        scan.visit_statement(body);
+        if scan.awaits.is_empty() || scan.exits_early {
+            return;
+        }
+        // an await that reads a value of the previous iteration, e.g. pagination cursors
+        let reads_previous_iteration = scan
+            .awaited_identifiers
+            .iter()
+            .any(|identifier| scan.assigned_identifiers.contains(identifier));
+        // a loop condition that checks an awaited result, e.g. `while (!done) { done = await step(); }`
+        // a counter like `i++` does not make the awaits depend on each other
+        let test_identifiers = test.map(referenced_identifiers).unwrap_or_default();
+        let condition_awaits = test_identifiers
+            .iter()
+            .any(|identifier| scan.awaited_assignments.contains(identifier));
+        let depends_on_previous_iteration = reads_previous_iteration || condition_awaits;
+        if !depends_on_previous_iteration {
+            self.matches
+                .push((scan.awaits.len(), span.start, scan.awaits[0]));
+        }
+    }
+}
+
+/// Visitor that collects the awaits and assignments of a single loop body
+#[derive(Debug, Default)]
+struct LoopScan {
+    /// start of every await that belongs to the scanned loop
+    awaits: Vec<u32>,
+    /// identifiers referenced by the awaited expressions
+    awaited_identifiers: HashSet<String>,
+    /// identifiers assigned in the loop body
+    assigned_identifiers: HashSet<String>,
+    /// identifiers assigned the result of an await in the loop body
+    awaited_assignments: HashSet<String>,
+    /// whether the loop can be left with `break` or `return`, e.g. retry loops
+    exits_early: bool,
+    /// depth of nested loops and switches, their awaits and breaks are handled separately
+    nesting: usize,
+}
+
+impl<'a> Visit<'a> for LoopScan {
+    // awaits in nested functions belong to those functions
+    fn visit_function(&mut self, _func: &Function<'a>, _flags: ScopeFlags) {}
+
+    fn visit_arrow_function_expression(&mut self, _expr: &ArrowFunctionExpression<'a>) {}
+
+    fn visit_await_expression(&mut self, expr: &AwaitExpression<'a>) {
+        if self.nesting == 0 {
+            self.awaits.push(expr.span.start);
+            self.awaited_identifiers
+                .extend(referenced_identifiers(&expr.argument));
+        }
+        walk_await_expression(self, expr);
+    }
+
+    fn visit_assignment_expression(&mut self, expr: &AssignmentExpression<'a>) {
+        let awaits = self.awaits.len();
+        walk_assignment_expression(self, expr);
+        if let AssignmentTarget::AssignmentTargetIdentifier(identifier) = &expr.left {
+            self.assigned_identifiers
+                .insert(identifier.name.to_string());
+            if self.awaits.len() > awaits {
+                self.awaited_assignments.insert(identifier.name.to_string());
+            }
+        }
+    }
+
+    fn visit_update_expression(&mut self, expr: &UpdateExpression<'a>) {
+        if let SimpleAssignmentTarget::AssignmentTargetIdentifier(identifier) = &expr.argument {
+            self.assigned_identifiers
+                .insert(identifier.name.to_string());
+        }
+        walk_update_expression(self, expr);
+    }
+
+    fn visit_break_statement(&mut self, stmt: &BreakStatement<'a>) {
+        if self.nesting == 0 || stmt.label.is_some() {
+            self.exits_early = true;
+        }
+    }
+
+    fn visit_return_statement(&mut self, _stmt: &ReturnStatement<'a>) {
+        self.exits_early = true;
+    }
+
+    fn visit_for_statement(&mut self, stmt: &ForStatement<'a>) {
+        self.nesting += 1;
+        walk_for_statement(self, stmt);
+        self.nesting -= 1;
+    }
+
+    fn visit_for_of_statement(&mut self, stmt: &ForOfStatement<'a>) {
+        self.nesting += 1;
+        walk_for_of_statement(self, stmt);
+        self.nesting -= 1;
+    }
+
+    fn visit_for_in_statement(&mut self, stmt: &ForInStatement<'a>) {
+        self.nesting += 1;
+        walk_for_in_statement(self, stmt);
+        self.nesting -= 1;
+    }
+
+    fn visit_while_statement(&mut self, stmt: &WhileStatement<'a>) {
+        self.nesting += 1;
+        walk_while_statement(self, stmt);
+        self.nesting -= 1;
+    }
+
+    fn visit_do_while_statement(&mut self, stmt: &DoWhileStatement<'a>) {
+        self.nesting += 1;
+        walk_do_while_statement(self, stmt);
+        self.nesting -= 1;
+    }
+
+    fn visit_switch_statement(&mut self, stmt: &SwitchStatement<'a>) {
+        self.nesting += 1;
+        walk_switch_statement(self, stmt);
+        self.nesting -= 1;
+    }
+}
+
+// region: tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::rules::js::apply_to_source;
+
+    #[test]
+    fn test_await_in_for_of_is_reported_at_loop() {
+        let source_text = "async function load(urls) {
+                const pages = [];
+                for (const url of urls) {
+                    const response = await fetch(url);
+                    pages.push(await response.text());
+                }
+                return pages;
+            }";
+        let result = apply_to_source(&SequentialAwait::default(), source_text);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].line, 3);
+        assert!(result[0]
+            .description
+            .starts_with("2 sequential 'await' in this loop (first at line 4)"));
+    }
+
+    #[test]
+    fn test_dependent_iterations_are_ignored() {
+        let source_text = "async function all(first) {
+                let cursor = first;
+                while (cursor) {
+                    const page = await fetchPage(cursor);
+                    cursor = page.next;
+                }
+                for (let attempt = 0; attempt < 3; attempt++) {
+                    if (await ping()) break;
+                }
+                for await (const chunk of stream) { await write(chunk); }
+            }";
+        let result = apply_to_source(&SequentialAwait::default(), source_text);
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_loop_counter_does_not_make_iterations_dependent() {
+        let source_text = "async function send(items, n) {
+                let i = 0;
+                while (i < n) {
+                    await post(items);
+                    i++;
+                }
+                let done = false;
+                while (!done) {
+                    done = await step();
+                }
+            }";
+        let result = apply_to_source(&SequentialAwait::default(), source_text);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].line, 3);
+    }
+
+    #[test]
+    fn test_await_in_nested_function_is_ignored() {
+        let source_text = "for (const id of ids) { queue.push(async () => await fetch(id)); }";
+        let result = apply_to_source(&SequentialAwait::default(), source_text);
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_nested_loops_report_innermost_loop() {
+        let source_text = "async function sync(groups) {
+                for (const group of groups) {
+                    for (const user of group.users) {
+                        await save(user);
+                    }
+                }
+            }";
+        let result = apply_to_source(&SequentialAwait::default(), source_text);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].line, 3);
+    }
+}
+// endregion: tests
@@ -0,0 +1,373 @@
+use std::collections::HashSet;
+
+use crate::model::ctx::Ctx;
+use crate::model::rules::LineResult;
+use crate::model::rules::Severity;
+
+use oxc::ast::ast::{
+    ArrowFunctionExpression, AssignmentExpression, CallExpression, Expression, FunctionBody,
+    ReturnStatement, Statement, VariableDeclarator,
+};
+use oxc::ast::visit::walk::{
+    walk_assignment_expression, walk_call_expression, walk_function, walk_variable_declarator,
+};
+use oxc::ast::Visit;
+use oxc::syntax::scope::ScopeFlags;
+
+use super::{line_column, numeric_value, Rule};
+
+/// canvas 2d context calls that draw a frame
+const CANVAS_CALLS: [&str; 12] = [
+    "clearRect",
+    "fillRect",
+    "strokeRect",
+    "drawImage",
+    "putImageData",
+    "fill",
+    "stroke",
+    "fillText",
+    "beginPath",
+    "translate",
+    "rotate",
+    "setTransform",
+];
+
+/// a timer that fires at an animation-like interval: timer name, callback name, inline callback animates, delay, start
+type AnimationTimer = (String, Option<String>, bool, f64, u32);
+
+/// This rule is used to find animations driven by timers and requestAnimationFrame loops that never stop
+#[derive(Debug)]
+pub struct TimerAnimation {
+    /// timers firing at least this often (in milliseconds) are considered animation timers
+    pub max_frame_interval_ms: f64,
+    timers: Vec<AnimationTimer>,
+    /// names of functions that change styles or draw on a canvas
+    animating_functions: HashSet<String>,
+    /// requestAnimationFrame loops without stop condition: function name, start
+    endless_frame_loops: Vec<(String, u32)>,
+    /// names of the functions the visitor is currently in
+    enclosing_functions: Vec<String>,
+    /// whether the program cancels animation frames anywhere
+    cancels_frames: bool,
+}
+
+impl Default for TimerAnimation {
+    fn default() -> Self {
+        TimerAnimation {
+            max_frame_interval_ms: 50.0,
+            timers: vec![],
+            animating_functions: HashSet::new(),
+            endless_frame_loops: vec![],
+            enclosing_functions: vec![],
+            cancels_frames: false,
+        }
+    }
+}
+
+impl Rule for TimerAnimation {
+    fn get_name(&self) -> &str {
+        "JS-Timer-Animation"
+    }
+    fn get_description(&self) -> &str {
+        "timers keep animating in background tabs and are not synchronized with the display. use 'requestAnimationFrame', which pauses while the page is hidden"
+    }
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        if let Ctx::JavaScriptCtx(js_ctx) = ctx {
+            let mut timer_animation = TimerAnimation {
+                max_frame_interval_ms: self.max_frame_interval_ms,
+                ..TimerAnimation::default()
+            };
+            timer_animation.visit_program(js_ctx.program);
+
+            let mut result = vec![];
+            for (timer, callback, animates, delay, start) in &timer_animation.timers {
+                let animates = *animates
+                    || callback.as_ref().is_some_and(|callback| {
+                        timer_animation.animating_functions.contains(callback)
+                    });
+                if !animates {
+                    continue;
+                }
+                let (line, column) = line_column(js_ctx.input, *start);
+                result.push(LineResult {
+                    severity: Severity::Warning,
+                    line,
+                    column,
+                    classification: self.get_name().to_string(),
+                    description: format!(
+                        "'{}' animates every {:.0} ms. {}",
+                        timer,
+                        delay,
+                        self.get_description()
+                    ),
+                });
+            }
+            // a loop that is stopped with cancelAnimationFrame somewhere is fine
+            if !timer_animation.cancels_frames {
+                for (name, start) in &timer_animation.endless_frame_loops {
+                    let (line, column) = line_column(js_ctx.input, *start);
+                    result.push(LineResult {
+                        severity: Severity::Info,
+                        line,
+                        column,
+                        classification: self.get_name().to_string(),
+                        description: format!("'{}' requests a new animation frame every time without a stop condition and renders 60 times per second even when nothing changes. stop the loop when the animation is finished", name),
+                    });
+                }
+            }
+            result.sort_by_key(|line_result| (line_result.line, line_result.column));
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a> Visit<'a> for TimerAnimation {
+    fn visit_call_expression(&mut self, expr: &CallExpression<'a>) {
+        match expr.callee_name() {
+            Some(name @ ("setInterval" | "setTimeout")) => {
+                if let Some(timer) = self.animation_timer(name, expr) {
+                    self.timers.push(timer);
+                }
+            }
+            Some("cancelAnimationFrame") => self.cancels_frames = true,
+            _ => {}
+        }
+        walk_call_expression(self, expr);
+    }
+
+    fn visit_function(&mut self, func: &oxc::ast::ast::Function<'a>, flags: ScopeFlags) {
+        match (&func.id, &func.body) {
+            (Some(id), Some(body)) => {
+                self.record_function(&id.name, body, id.span.start);
+                self.enclosing_functions.push(id.name.to_string());
+                walk_function(self, func, flags);
+                self.enclosing_functions.pop();
+            }
+            _ => walk_function(self, func, flags),
+        }
+    }
+
+    fn visit_variable_declarator(&mut self, declarator: &VariableDeclarator<'a>) {
+        // `const step = () => { ... }` is treated like a function declaration named step
+        let body = match &declarator.init {
+            Some(Expression::ArrowFunctionExpression(arrow)) => Some(&arrow.body),
+            Some(Expression::FunctionExpression(func)) => func.body.as_ref(),
+            _ => None,
+        };
+        match (declarator.id.get_identifier(), body) {
+            (Some(name), Some(body)) => {
+                self.record_function(&name, body, declarator.span.start);
+                self.enclosing_functions.push(name.to_string());
+                walk_variable_declarator(self, declarator);
+                self.enclosing_functions.pop();
+            }
+            _ => walk_variable_declarator(self, declarator),
+        }
+    }
+}
+
+impl TimerAnimation {
+    /// Build the timer if it fires at an animation-like interval
+    /// setTimeout is only considered if it schedules the function it is called from
+    fn animation_timer(&self, name: &str, expr: &CallExpression<'_>) -> Option<AnimationTimer> {
+        let callback = expr.arguments.first()?.as_expression()?;
+        let delay = match expr.arguments.get(1) {
+            Some(delay) => numeric_value(delay.as_expression()?)?,
+            None => 0.0,
+        };
+        if delay > self.max_frame_interval_ms {
+            return None;
+        }
+        let callback_name = match callback {
+            Expression::Identifier(identifier) => Some(identifier.name.to_string()),
+            _ => None,
+        };
+        if name == "setTimeout"
+            && !callback_name
+                .as_ref()
+                .is_some_and(|callback_name| self.enclosing_functions.contains(callback_name))
+        {
+            return None;
+        }
+        let mut finder = AnimationFinder::default();
+        finder.visit_expression(callback);
+        Some((
+            name.to_string(),
+            callback_name,
+            finder.found,
+            delay,
+            expr.span.start,
+        ))
+    }
+
+    /// Remember whether the function animates and whether it is an endless requestAnimationFrame loop
+    fn record_function(&mut self, name: &str, body: &FunctionBody<'_>, start: u32) {
+        let mut finder = AnimationFinder::default();
+        finder.visit_function_body(body);
+        if finder.found {
+            self.animating_functions.insert(name.to_string());
+        }
+        if requests_itself_unconditionally(name, body) && !has_return(body) {
+            self.endless_frame_loops.push((name.to_string(), start));
+        }
+    }
+}
+
+/// Visitor that records whether the visited code changes styles or draws on a canvas
+#[derive(Debug, Default)]
+struct AnimationFinder {
+    found: bool,
+}
+
+impl<'a> Visit<'a> for AnimationFinder {
+    fn visit_assignment_expression(&mut self, expr: &AssignmentExpression<'a>) {
+        // `element.style.left = x + 'px'`
+        if let Some(member) = expr.left.as_member_expression() {
+            if touches_style(member.object()) {
+                self.found = true;
+            }
+        }
+        walk_assignment_expression(self, expr);
+    }
+
+    fn visit_call_expression(&mut self, expr: &CallExpression<'a>) {
+        // `context.fillRect(...)` or `element.style.setProperty(...)`
+        let is_canvas_call = expr
+            .callee_name()
+            .is_some_and(|name| CANVAS_CALLS.contains(&name));
+        let is_style_call = expr
+            .callee
+            .get_member_expr()
+            .is_some_and(|member| touches_style(member.object()));
+        if is_canvas_call || is_style_call {
+            self.found = true;
+        }
+        walk_call_expression(self, expr);
+    }
+}
+
+/// Visitor that records whether a function body returns early, nested functions are skipped
+#[derive(Debug, Default)]
+struct ReturnFinder {
+    found: bool,
+}
+
+impl<'a> Visit<'a> for ReturnFinder {
+    fn visit_return_statement(&mut self, _stmt: &ReturnStatement<'a>) {
+        self.found = true;
+    }
+
+    fn visit_function(&mut self, _func: &oxc::ast::ast::Function<'a>, _flags: ScopeFlags) {}
+
+    fn visit_arrow_function_expression(&mut self, _expr: &ArrowFunctionExpression<'a>) {}
+}
+
+// region: helpers
+
+/// Check if the expression is a `.style` access or an object reached through one
+fn touches_style(expr: &Expression<'_>) -> bool {
+    match expr.get_member_expr() {
+        Some(member) => {
+            member.static_property_name() == Some("style") || touches_style(member.object())
+        }
+        None => false,
+    }
+}
+
+/// Check if the body calls `requestAnimationFrame(name)` as a top level statement, outside of any condition
+fn requests_itself_unconditionally(name: &str, body: &FunctionBody<'_>) -> bool {
+    body.statements.iter().any(|statement| {
+        let Statement::ExpressionStatement(statement) = statement else {
+            return false;
+        };
+        // `requestAnimationFrame(step)` or `frame = requestAnimationFrame(step)`
+        let call = match &statement.expression {
+            Expression::CallExpression(call) => call,
+            Expression::AssignmentExpression(assignment) => match &assignment.right {
+                Expression::CallExpression(call) => call,
+                _ => return false,
+            },
+            _ => return false,
+        };
+        call.callee_name() == Some("requestAnimationFrame")
+            && call
+                .arguments
+                .first()
+                .and_then(|argument| argument.as_expression())
+                .is_some_and(|argument| argument.is_specific_id(name))
+    })
+}
+
+/// Check if the body contains a return statement that can end the loop
+fn has_return(body: &FunctionBody<'_>) -> bool {
+    let mut finder = ReturnFinder::default();
+    finder.visit_function_body(body);
+    finder.found
+}
+
+// endregion: helpers
+// region: tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::rules::js::apply_to_source;
+
+    #[test]
+    fn test_interval_animating_style_is_reported() {
+        let source_text = "let x = 0;
+            setInterval(() => { box.style.left = (x++) + 'px'; }, 16);
+            setInterval(() => { counter.textContent = x; }, 16);
+            setInterval(() => { box.style.left = '0px'; }, 1000);";
+        let result = apply_to_source(&TimerAnimation::default(), source_text);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].line, 2);
+        assert!(result[0]
+            .description
+            .starts_with("'setInterval' animates every 16 ms"));
+    }
+
+    #[test]
+    fn test_recursive_timeout_drawing_on_canvas_is_reported() {
+        let source_text = "function draw() {
+                context.clearRect(0, 0, width, height);
+                context.fillRect(x, y, 10, 10);
+                setTimeout(draw, 1000 / 60);
+            }
+            draw();
+            setTimeout(() => { banner.style.opacity = 0; }, 10);";
+        let result = apply_to_source(&TimerAnimation::default(), source_text);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].line, 4);
+    }
+
+    #[test]
+    fn test_endless_frame_loop_is_reported() {
+        let source_text = "function spin() {
+                wheel.style.transform = `rotate(${angle++}deg)`;
+                requestAnimationFrame(spin);
+            }
+            const fade = () => {
+                if (opacity <= 0) return;
+                opacity -= 0.05;
+                requestAnimationFrame(fade);
+            };
+            const glow = () => {
+                if (active) requestAnimationFrame(glow);
+            };";
+        let result = apply_to_source(&TimerAnimation::default(), source_text);
+        assert_eq!(result.len(), 1);
+        assert!(matches!(result[0].severity, Severity::Info));
+        assert!(result[0].description.starts_with("'spin'"));
+    }
+
+    #[test]
+    fn test_cancel_animation_frame_is_a_stop_condition() {
+        let source_text = "function spin() { frame = requestAnimationFrame(spin); }
+            stopButton.onclick = () => cancelAnimationFrame(frame);";
+        let result = apply_to_source(&TimerAnimation::default(), source_text);
+        assert!(result.is_empty());
+    }
+}
+// endregion: tests
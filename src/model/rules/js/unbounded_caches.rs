@@ -0,0 +1,336 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::model::ctx::Ctx;
+use crate::model::rules::LineResult;
+use crate::model::rules::Severity;
+
+use oxc::ast::ast::{
+    ArrowFunctionExpression, AssignmentExpression, AssignmentTarget, CallExpression, Expression,
+    Function, MemberExpression, Statement,
+};
+use oxc::ast::visit::walk::{
+    walk_arrow_function_expression, walk_assignment_expression, walk_call_expression,
+    walk_function, walk_member_expression,
+};
+use oxc::ast::Visit;
+use oxc::semantic::{ReferenceId, Semantic, SymbolFlags, SymbolId};
+use oxc::syntax::scope::ScopeFlags;
+
+use super::{line_column, resolve_symbol, Rule};
+
+/// a growing collection type with the methods that add and remove entries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Collection {
+    Map,
+    Set,
+    Array,
+}
+
+impl Collection {
+    fn name(self) -> &'static str {
+        match self {
+            Collection::Map => "Map",
+            Collection::Set => "Set",
+            Collection::Array => "array",
+        }
+    }
+
+    fn adding_methods(self) -> &'static [&'static str] {
+        match self {
+            Collection::Map => &["set"],
+            Collection::Set => &["add"],
+            Collection::Array => &["push", "unshift"],
+        }
+    }
+
+    fn removing_methods(self) -> &'static [&'static str] {
+        match self {
+            Collection::Map | Collection::Set => &["delete", "clear"],
+            Collection::Array => &["pop", "shift", "splice"],
+        }
+    }
+}
+
+/// how a module-level collection is used
+#[derive(Debug, Default)]
+struct Usage {
+    /// number of calls that add entries inside functions
+    additions: usize,
+    /// whether entries are ever removed, the collection is truncated or replaced
+    evicted: bool,
+}
+
+/// This rule is used to find module-level collections that grow for the whole lifetime of the page
+pub struct UnboundedCaches;
+
+impl Rule for UnboundedCaches {
+    fn get_name(&self) -> &str {
+        "JS-Unbounded-Caches"
+    }
+    fn get_description(&self) -> &str {
+        "a module-level collection that only grows keeps every entry in memory until the page is closed. limit its size and evict old entries, or use a 'WeakMap' keyed by the objects"
+    }
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        if let Ctx::JavaScriptCtx(js_ctx) = ctx {
+            let collections = module_collections(js_ctx.semantic, &js_ctx.program.body);
+            let mut finder = UsageFinder {
+                semantic: js_ctx.semantic,
+                collections: &collections,
+                usages: HashMap::new(),
+                member_references: HashSet::new(),
+                function_depth: 0,
+            };
+            finder.visit_program(js_ctx.program);
+
+            let symbols = js_ctx.semantic.symbols();
+            let mut matches = vec![];
+            for (symbol_id, (collection, start)) in &collections {
+                let Some(usage) = finder.usages.get(symbol_id) else {
+                    continue;
+                };
+                // a collection passed around or returned may be cleaned up elsewhere
+                let escapes =
+                    symbols
+                        .get_resolved_reference_ids(*symbol_id)
+                        .iter()
+                        .any(|reference_id| {
+                            symbols.get_reference(*reference_id).is_read()
+                                && !finder.member_references.contains(reference_id)
+                        });
+                if usage.additions == 0 || usage.evicted || escapes {
+                    continue;
+                }
+                matches.push((
+                    *start,
+                    format!(
+                        "module-level {} '{}' gets entries added in {} place(s) but never removed with {}. {}",
+                        collection.name(),
+                        symbols.get_name(*symbol_id),
+                        usage.additions,
+                        collection
+                            .removing_methods()
+                            .iter()
+                            .map(|method| format!("'{}'", method))
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                        self.get_description()
+                    ),
+                ));
+            }
+            matches.sort_by_key(|(start, _)| *start);
+
+            let mut result = vec![];
+            for (start, description) in matches {
+                let (line, column) = line_column(js_ctx.input, start);
+                result.push(LineResult {
+                    severity: Severity::Warning,
+                    line,
+                    column,
+                    classification: self.get_name().to_string(),
+                    description,
+                });
+            }
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+/// Visitor that records how the module-level collections are used
+struct UsageFinder<'s, 'a> {
+    semantic: &'s Semantic<'a>,
+    collections: &'s HashMap<SymbolId, (Collection, u32)>,
+    usages: HashMap<SymbolId, Usage>,
+    /// references that are the object of a member expression like `cache.get`
+    member_references: HashSet<ReferenceId>,
+    /// number of functions around the current node
+    function_depth: usize,
+}
+
+impl<'a> Visit<'a> for UsageFinder<'_, 'a> {
+    fn visit_call_expression(&mut self, expr: &CallExpression<'a>) {
+        // `cache.set(key, value)` or `cache.delete(key)`
+        if let Some(member) = expr.callee.get_member_expr() {
+            if let (Some((symbol_id, collection)), Some(method)) = (
+                self.collection(member.object()),
+                member.static_property_name(),
+            ) {
+                if collection.adding_methods().contains(&method) && self.function_depth > 0 {
+                    self.usage(symbol_id).additions += 1;
+                } else if collection.removing_methods().contains(&method) {
+                    self.usage(symbol_id).evicted = true;
+                }
+            }
+        }
+        walk_call_expression(self, expr);
+    }
+
+    fn visit_assignment_expression(&mut self, expr: &AssignmentExpression<'a>) {
+        match &expr.left {
+            // `cache = new Map()` replaces the collection
+            AssignmentTarget::AssignmentTargetIdentifier(identifier) => {
+                if let Some(symbol_id) = resolve_symbol(self.semantic, identifier)
+                    .filter(|symbol_id| self.collections.contains_key(symbol_id))
+                {
+                    self.usage(symbol_id).evicted = true;
+                }
+            }
+            target => {
+                if let Some(member) = target.as_member_expression() {
+                    if let Some((symbol_id, Collection::Array)) = self.collection(member.object()) {
+                        if member.static_property_name() == Some("length") {
+                            // `items.length = 0` truncates the array
+                            self.usage(symbol_id).evicted = true;
+                        } else if self.function_depth > 0 {
+                            // `items[items.length] = item`
+                            self.usage(symbol_id).additions += 1;
+                        }
+                    }
+                }
+            }
+        }
+        walk_assignment_expression(self, expr);
+    }
+
+    fn visit_member_expression(&mut self, expr: &MemberExpression<'a>) {
+        if let Expression::Identifier(identifier) = expr.object() {
+            if let Some(reference_id) = identifier.reference_id() {
+                self.member_references.insert(reference_id);
+            }
+        }
+        walk_member_expression(self, expr);
+    }
+
+    fn visit_function(&mut self, func: &Function<'a>, flags: ScopeFlags) {
+        self.function_depth += 1;
+        walk_function(self, func, flags);
+        self.function_depth -= 1;
+    }
+
+    fn visit_arrow_function_expression(&mut self, expr: &ArrowFunctionExpression<'a>) {
+        self.function_depth += 1;
+        walk_arrow_function_expression(self, expr);
+        self.function_depth -= 1;
+    }
+}
+
+impl UsageFinder<'_, '_> {
+    /// Resolve the expression to one of the module-level collections
+    fn collection(&self, expr: &Expression<'_>) -> Option<(SymbolId, Collection)> {
+        let Expression::Identifier(identifier) = expr else {
+            return None;
+        };
+        let symbol_id = resolve_symbol(self.semantic, identifier)?;
+        let (collection, _) = self.collections.get(&symbol_id)?;
+        Some((symbol_id, *collection))
+    }
+
+    fn usage(&mut self, symbol_id: SymbolId) -> &mut Usage {
+        self.usages.entry(symbol_id).or_default()
+    }
+}
+
+// region: helpers
+
+/// Find the collections declared at the top level of the program, exported collections are not included
+fn module_collections(
+    semantic: &Semantic<'_>,
+    body: &[Statement<'_>],
+) -> HashMap<SymbolId, (Collection, u32)> {
+    let mut collections = HashMap::new();
+    for statement in body {
+        let Statement::VariableDeclaration(declaration) = statement else {
+            continue;
+        };
+        for declarator in &declaration.declarations {
+            let (Some(identifier), Some(init)) =
+                (declarator.id.get_binding_identifier(), &declarator.init)
+            else {
+                continue;
+            };
+            let Some(symbol_id) = identifier.symbol_id.get() else {
+                continue;
+            };
+            if semantic
+                .symbols()
+                .get_flags(symbol_id)
+                .contains(SymbolFlags::Export)
+            {
+                continue;
+            }
+            let collection = match init.get_inner_expression() {
+                Expression::NewExpression(new) if new.callee.is_specific_id("Map") => {
+                    Collection::Map
+                }
+                Expression::NewExpression(new) if new.callee.is_specific_id("Set") => {
+                    Collection::Set
+                }
+                Expression::ArrayExpression(_) => Collection::Array,
+                _ => continue,
+            };
+            collections.insert(symbol_id, (collection, declarator.span.start));
+        }
+    }
+    collections
+}
+
+// endregion: helpers
+// region: tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::rules::js::apply_to_source;
+
+    #[test]
+    fn test_growing_collections_are_reported_at_declaration() {
+        let source_text = "const responses = new Map();
+            const seen = new Set();
+            const history = [];
+            const visits = [];
+            async function load(url) {
+                if (!responses.has(url)) responses.set(url, await fetch(url));
+                seen.add(url);
+                history.push(url);
+                visits[visits.length] = Date.now();
+                return responses.get(url);
+            }";
+        let result = apply_to_source(&UnboundedCaches, source_text);
+        assert_eq!(result.len(), 4);
+        assert_eq!(result[0].line, 1);
+        assert!(result[0]
+            .description
+            .starts_with("module-level Map 'responses' gets entries added in 1 place(s)"));
+        assert_eq!(result[2].line, 3);
+    }
+
+    #[test]
+    fn test_evicted_and_static_collections_are_ignored() {
+        let source_text = "const cache = new Map();
+            const lookup = new Map();
+            lookup.set('a', 1);
+            let queue = [];
+            const log = [];
+            function remember(key, value) {
+                if (cache.size > 100) cache.delete(cache.keys().next().value);
+                cache.set(key, value);
+                queue.push(value);
+                log.push(value);
+                if (log.length > 50) log.length = 0;
+            }
+            function flush() { send(queue); queue = []; }";
+        let result = apply_to_source(&UnboundedCaches, source_text);
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_escaping_and_local_collections_are_ignored() {
+        let source_text = "const registry = new Set();
+            function register(item) { registry.add(item); }
+            cleanup(registry);
+            function local() { const items = []; items.push(1); return items; }";
+        let result = apply_to_source(&UnboundedCaches, source_text);
+        assert!(result.is_empty());
+    }
+}
+// endregion: tests
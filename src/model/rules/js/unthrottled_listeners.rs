@@ -0,0 +1,209 @@
+use std::collections::HashSet;
+
+use crate::model::ctx::Ctx;
+use crate::model::rules::LineResult;
+use crate::model::rules::Severity;
+
+use oxc::ast::ast::{Argument, Expression, ObjectPropertyKind, VariableDeclarator};
+use oxc::ast::visit::walk::{walk_call_expression, walk_function, walk_variable_declarator};
+use oxc::ast::Visit;
+use oxc::syntax::scope::ScopeFlags;
+
+use super::{calls_any, line_column, CallFinder, Rule};
+
+/// events that fire many times per second while the user interacts with the page
+const HIGH_FREQUENCY_EVENTS: [&str; 4] = ["scroll", "resize", "mousemove", "touchmove"];
+
+/// helpers that limit how often a handler runs, e.g. lodash `throttle`/`debounce`
+const THROTTLE_HELPERS: [&str; 6] = [
+    "throttle",
+    "debounce",
+    "rafThrottle",
+    "rafSchd",
+    "throttleTime",
+    "debounceTime",
+];
+
+/// handlers calling one of these only schedule work for the next frame
+const FRAME_SCHEDULERS: [&str; 1] = ["requestAnimationFrame"];
+
+/// This rule is used to find scroll, resize and mousemove listeners that run on every event
+#[derive(Debug, Default)]
+pub struct UnthrottledListeners {
+    /// event name, start, end
+    matches: Vec<(String, u32, u32)>,
+    /// listeners whose handler is passed by name: event name, start, end, handler name
+    named_handlers: Vec<(String, u32, u32, String)>,
+    /// names of handlers that are already throttled, debounced or scheduled with requestAnimationFrame
+    throttled_handlers: HashSet<String>,
+}
+
+impl Rule for UnthrottledListeners {
+    fn get_name(&self) -> &str {
+        "JS-Unthrottled-Listeners"
+    }
+    fn get_description(&self) -> &str {
+        "this listener runs on every event and keeps the CPU busy. register it with '{ passive: true }' and wrap the handler with a throttle/debounce helper (e.g. lodash 'throttle') or schedule the work with 'requestAnimationFrame'"
+    }
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        if let Ctx::JavaScriptCtx(js_ctx) = ctx {
+            let mut listeners = UnthrottledListeners::default();
+            listeners.visit_program(js_ctx.program);
+
+            // handlers passed by name are only reported if their declaration is not throttled
+            let named_matches = listeners
+                .named_handlers
+                .iter()
+                .filter(|(_, _, _, handler)| !listeners.throttled_handlers.contains(handler))
+                .map(|(event, start, end, _)| (event.clone(), *start, *end));
+            let matches: Vec<(String, u32, u32)> = listeners
+                .matches
+                .iter()
+                .cloned()
+                .chain(named_matches)
+                .collect();
+
+            let mut result = vec![];
+            for (event, start, _end) in matches {
+                let (line, column) = line_column(js_ctx.input, start);
+                result.push(LineResult {
+                    severity: Severity::Warning,
+                    line,
+                    column,
+                    classification: self.get_name().to_string(),
+                    description: format!("'{}' {}", event, self.get_description()),
+                });
+            }
+            result.sort_by_key(|line_result| (line_result.line, line_result.column));
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a> Visit<'a> for UnthrottledListeners {
+    fn visit_call_expression(&mut self, expr: &oxc::ast::ast::CallExpression<'a>) {
+        if expr.callee_name() == Some("addEventListener") && expr.arguments.len() >= 2 {
+            if let Argument::StringLiteral(event) = &expr.arguments[0] {
+                let event = event.value.as_str();
+                if HIGH_FREQUENCY_EVENTS.contains(&event) && !is_passive(expr.arguments.get(2)) {
+                    let (start, end) = (expr.span.start, expr.span.end);
+                    match expr.arguments[1].as_expression() {
+                        Some(Expression::Identifier(handler)) => self.named_handlers.push((
+                            event.to_string(),
+                            start,
+                            end,
+                            handler.name.to_string(),
+                        )),
+                        Some(handler) if is_throttled(handler) => {}
+                        _ => self.matches.push((event.to_string(), start, end)),
+                    }
+                }
+            }
+        }
+        // continue walking the AST
+        walk_call_expression(self, expr);
+    }
+
+    fn visit_variable_declarator(&mut self, declarator: &VariableDeclarator<'a>) {
+        // `const onScroll = throttle(update, 100)`
+        if let (Some(name), Some(init)) = (declarator.id.get_identifier(), &declarator.init) {
+            if is_throttled(init) {
+                self.throttled_handlers.insert(name.to_string());
+            }
+        }
+        walk_variable_declarator(self, declarator);
+    }
+
+    fn visit_function(&mut self, func: &oxc::ast::ast::Function<'a>, flags: ScopeFlags) {
+        // `function onScroll() { requestAnimationFrame(update) }`
+        if let (Some(id), Some(body)) = (&func.id, &func.body) {
+            let mut finder = CallFinder {
+                names: &FRAME_SCHEDULERS,
+                found: false,
+            };
+            finder.visit_function_body(body);
+            if finder.found {
+                self.throttled_handlers.insert(id.name.to_string());
+            }
+        }
+        walk_function(self, func, flags);
+    }
+}
+
+// region: helpers
+
+/// Check if the listener options are an object literal containing `passive: true`
+fn is_passive(options: Option<&Argument<'_>>) -> bool {
+    let Some(Expression::ObjectExpression(options)) = options.and_then(Argument::as_expression)
+    else {
+        return false;
+    };
+    options.properties.iter().any(|property| {
+        matches!(property, ObjectPropertyKind::ObjectProperty(property)
+            if property.key.is_specific_static_name("passive")
+                && property.value.get_boolean_value() == Some(true))
+    })
+}
+
+/// Check if the handler is wrapped by a throttle helper or only schedules work with requestAnimationFrame
+fn is_throttled(handler: &Expression<'_>) -> bool {
+    match handler.get_inner_expression() {
+        Expression::CallExpression(call) => call
+            .callee_name()
+            .is_some_and(|name| THROTTLE_HELPERS.contains(&name)),
+        handler @ (Expression::ArrowFunctionExpression(_) | Expression::FunctionExpression(_)) => {
+            calls_any(handler, &FRAME_SCHEDULERS)
+        }
+        _ => false,
+    }
+}
+
+// endregion: helpers
+// region: tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::rules::js::apply_to_source;
+
+    #[test]
+    fn test_inline_scroll_handler_is_reported() {
+        let source_text = "window.addEventListener('scroll', () => { header.classList.toggle('small', window.scrollY > 50); });";
+        let result = apply_to_source(&UnthrottledListeners::default(), source_text);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].line, 1);
+        assert_eq!(result[0].column, 1);
+    }
+
+    #[test]
+    fn test_passive_and_other_events_are_ignored() {
+        let source_text = "window.addEventListener('scroll', onScroll, { passive: true });
+            button.addEventListener('click', onClick);";
+        let result = apply_to_source(&UnthrottledListeners::default(), source_text);
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_throttle_helpers_are_recognized() {
+        let source_text = "window.addEventListener('resize', _.debounce(layout, 200));
+            const onMove = throttle(track, 100);
+            document.addEventListener('mousemove', onMove);
+            function onTouch() { requestAnimationFrame(draw); }
+            canvas.addEventListener('touchmove', onTouch);
+            window.addEventListener('scroll', () => requestAnimationFrame(update));";
+        let result = apply_to_source(&UnthrottledListeners::default(), source_text);
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_named_handler_without_throttle_is_reported() {
+        let source_text = "function onMove(event) { cursor.style.left = event.clientX + 'px'; }
+            document.addEventListener('mousemove', onMove);";
+        let result = apply_to_source(&UnthrottledListeners::default(), source_text);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].line, 2);
+        assert!(result[0].description.starts_with("'mousemove'"));
+    }
+}
+// endregion: tests
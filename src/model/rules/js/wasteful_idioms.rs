@@ -0,0 +1,416 @@
+use std::collections::HashSet;
+
+use crate::model::ctx::Ctx;
+use crate::model::rules::LineResult;
+use crate::model::rules::Severity;
+
+use oxc::ast::ast::{
+    Argument, ArrayExpressionElement, ArrowFunctionExpression, AssignmentExpression,
+    AssignmentTarget, CallExpression, DoWhileStatement, Expression, ForInStatement, ForOfStatement,
+    ForStatement, Function, NewExpression, SpreadElement, StaticMemberExpression,
+    VariableDeclarator, WhileStatement,
+};
+use oxc::ast::visit::walk::{
+    walk_arrow_function_expression, walk_assignment_expression, walk_call_expression,
+    walk_do_while_statement, walk_for_in_statement, walk_for_of_statement, walk_for_statement,
+    walk_function, walk_new_expression, walk_spread_element, walk_static_member_expression,
+    walk_variable_declarator, walk_while_statement,
+};
+use oxc::ast::Visit;
+use oxc::syntax::operator::AssignmentOperator;
+use oxc::syntax::scope::ScopeFlags;
+
+use super::{line_column, Rule};
+
+/// array methods whose callback runs once per element, their callbacks are treated like loop bodies
+const ITERATION_METHODS: [&str; 8] = [
+    "forEach", "map", "filter", "reduce", "some", "every", "find", "flatMap",
+];
+
+/// the CPU-wasting idioms this rule pack recognizes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Idiom {
+    JsonDeepClone,
+    StringConcatInLoop,
+    RegExpInLoop,
+    ReduceSpread,
+    ArrayFromLength,
+}
+
+// region: rules
+
+/// This rule is used to find `JSON.parse(JSON.stringify(x))` deep clones
+pub struct JsonDeepClone;
+impl Rule for JsonDeepClone {
+    fn get_name(&self) -> &str {
+        "JS-JSON-Deep-Clone"
+    }
+    fn get_description(&self) -> &str {
+        "'JSON.parse(JSON.stringify(x))' serializes the whole object to a string and parses it again. use 'structuredClone(x)' to copy it directly"
+    }
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        apply_idiom(self, ctx, Idiom::JsonDeepClone, Severity::Warning)
+    }
+}
+
+/// This rule is used to find strings built with `+=` inside loops
+pub struct StringConcatInLoop;
+impl Rule for StringConcatInLoop {
+    fn get_name(&self) -> &str {
+        "JS-String-Concat-In-Loop"
+    }
+    fn get_description(&self) -> &str {
+        "building a string with '+=' inside a loop creates a new intermediate string in every iteration. collect the parts in an array and call 'join' once"
+    }
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        apply_idiom(self, ctx, Idiom::StringConcatInLoop, Severity::Warning)
+    }
+}
+
+/// This rule is used to find regular expressions constructed inside loops
+pub struct RegExpInLoop;
+impl Rule for RegExpInLoop {
+    fn get_name(&self) -> &str {
+        "JS-RegExp-In-Loop"
+    }
+    fn get_description(&self) -> &str {
+        "'new RegExp' inside a loop compiles the same pattern again in every iteration. create the regular expression once before the loop"
+    }
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        apply_idiom(self, ctx, Idiom::RegExpInLoop, Severity::Warning)
+    }
+}
+
+/// This rule is used to find `reduce` callbacks that spread the accumulator
+pub struct ReduceSpread;
+impl Rule for ReduceSpread {
+    fn get_name(&self) -> &str {
+        "JS-Reduce-Spread"
+    }
+    fn get_description(&self) -> &str {
+        "spreading the accumulator in 'reduce' copies it in every iteration, which makes the reduction quadratic. mutate the accumulator ('acc[key] = value; return acc') or use a loop"
+    }
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        apply_idiom(self, ctx, Idiom::ReduceSpread, Severity::Warning)
+    }
+}
+
+/// This rule is used to find `Array.from(x).length` and `[...x].length` conversions
+pub struct ArrayFromLength;
+impl Rule for ArrayFromLength {
+    fn get_name(&self) -> &str {
+        "JS-Array-From-Length"
+    }
+    fn get_description(&self) -> &str {
+        "'Array.from(x).length' copies every element only to count them. use 'x.length' or 'x.size' directly"
+    }
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        apply_idiom(self, ctx, Idiom::ArrayFromLength, Severity::Info)
+    }
+}
+
+// endregion: rules
+
+/// Find all occurrences of the idiom and report them with the description of the rule
+fn apply_idiom(
+    rule: &dyn Rule,
+    ctx: &Ctx<'_>,
+    idiom: Idiom,
+    severity: Severity,
+) -> Option<Vec<LineResult>> {
+    if let Ctx::JavaScriptCtx(js_ctx) = ctx {
+        let mut finder = IdiomFinder::default();
+        finder.visit_program(js_ctx.program);
+        let result = finder
+            .matches
+            .iter()
+            .filter(|(found, _, _)| *found == idiom)
+            .map(|(_, start, _end)| {
+                let (line, column) = line_column(js_ctx.input, *start);
+                LineResult {
+                    severity: severity.clone(),
+                    line,
+                    column,
+                    classification: rule.get_name().to_string(),
+                    description: rule.get_description().to_string(),
+                }
+            })
+            .collect();
+        Some(result)
+    } else {
+        None
+    }
+}
+
+/// Visitor that finds all idioms of the rule pack in one pass
+#[derive(Debug, Default)]
+struct IdiomFinder {
+    /// idiom, start, end
+    matches: Vec<(Idiom, u32, u32)>,
+    /// number of loops around the current node within the current function
+    loop_depth: usize,
+    /// loop depths of the enclosing functions
+    saved_loop_depths: Vec<usize>,
+    /// set while visiting the arguments of an iteration method like `forEach`
+    iteration_callback: bool,
+    /// variables initialized with a string, `+=` on them is string concatenation
+    string_variables: HashSet<String>,
+}
+
+impl<'a> Visit<'a> for IdiomFinder {
+    fn visit_call_expression(&mut self, expr: &CallExpression<'a>) {
+        if is_json_deep_clone(expr) {
+            self.push(Idiom::JsonDeepClone, expr.span);
+        }
+        if expr.callee_name() == Some("reduce") && spreads_accumulator(expr) {
+            self.push(Idiom::ReduceSpread, expr.span);
+        }
+        if expr.callee.is_specific_id("RegExp") && self.loop_depth > 0 {
+            self.push(Idiom::RegExpInLoop, expr.span);
+        }
+        if expr
+            .callee_name()
+            .is_some_and(|name| ITERATION_METHODS.contains(&name))
+            && expr.callee.get_member_expr().is_some()
+        {
+            self.visit_expression(&expr.callee);
+            self.iteration_callback = true;
+            self.visit_arguments(&expr.arguments);
+            self.iteration_callback = false;
+        } else {
+            walk_call_expression(self, expr);
+        }
+    }
+
+    fn visit_new_expression(&mut self, expr: &NewExpression<'a>) {
+        if expr.callee.is_specific_id("RegExp") && self.loop_depth > 0 {
+            self.push(Idiom::RegExpInLoop, expr.span);
+        }
+        walk_new_expression(self, expr);
+    }
+
+    fn visit_assignment_expression(&mut self, expr: &AssignmentExpression<'a>) {
+        if expr.operator == AssignmentOperator::Addition && self.loop_depth > 0 {
+            if let AssignmentTarget::AssignmentTargetIdentifier(identifier) = &expr.left {
+                if self.string_variables.contains(identifier.name.as_str())
+                    || is_string_like(&expr.right)
+                {
+                    self.push(Idiom::StringConcatInLoop, expr.span);
+                }
+            }
+        }
+        walk_assignment_expression(self, expr);
+    }
+
+    fn visit_static_member_expression(&mut self, expr: &StaticMemberExpression<'a>) {
+        if expr.property.name == "length" && is_array_copy(&expr.object) {
+            self.push(Idiom::ArrayFromLength, expr.span);
+        }
+        walk_static_member_expression(self, expr);
+    }
+
+    fn visit_variable_declarator(&mut self, declarator: &VariableDeclarator<'a>) {
+        if let (Some(name), Some(init)) = (declarator.id.get_identifier(), &declarator.init) {
+            if is_string_like(init) {
+                self.string_variables.insert(name.to_string());
+            }
+        }
+        walk_variable_declarator(self, declarator);
+    }
+
+    fn visit_function(&mut self, func: &Function<'a>, flags: ScopeFlags) {
+        self.enter_function();
+        walk_function(self, func, flags);
+        self.leave_function();
+    }
+
+    fn visit_arrow_function_expression(&mut self, expr: &ArrowFunctionExpression<'a>) {
+        self.enter_function();
+        walk_arrow_function_expression(self, expr);
+        self.leave_function();
+    }
+
+    fn visit_for_statement(&mut self, stmt: &ForStatement<'a>) {
+        self.loop_depth += 1;
+        walk_for_statement(self, stmt);
+        self.loop_depth -= 1;
+    }
+
+    fn visit_for_of_statement(&mut self, stmt: &ForOfStatement<'a>) {
+        self.loop_depth += 1;
+        walk_for_of_statement(self, stmt);
+        self.loop_depth -= 1;
+    }
+
+    fn visit_for_in_statement(&mut self, stmt: &ForInStatement<'a>) {
+        self.loop_depth += 1;
+        walk_for_in_statement(self, stmt);
+        self.loop_depth -= 1;
+    }
+
+    fn visit_while_statement(&mut self, stmt: &WhileStatement<'a>) {
+        self.loop_depth += 1;
+        walk_while_statement(self, stmt);
+        self.loop_depth -= 1;
+    }
+
+    fn visit_do_while_statement(&mut self, stmt: &DoWhileStatement<'a>) {
+        self.loop_depth += 1;
+        walk_do_while_statement(self, stmt);
+        self.loop_depth -= 1;
+    }
+}
+
+impl IdiomFinder {
+    fn push(&mut self, idiom: Idiom, span: oxc::span::Span) {
+        self.matches.push((idiom, span.start, span.end));
+    }
+
+    /// A function body starts outside of any loop, unless it is the callback of an iteration method
+    fn enter_function(&mut self) {
+        self.saved_loop_depths.push(self.loop_depth);
+        self.loop_depth = usize::from(std::mem::take(&mut self.iteration_callback));
+    }
+
+    fn leave_function(&mut self) {
+        self.loop_depth = self.saved_loop_depths.pop().unwrap_or_default();
+    }
+}
+
+// region: helpers
+
+/// Check if the call is `JSON.parse(JSON.stringify(x))`
+fn is_json_deep_clone(expr: &CallExpression<'_>) -> bool {
+    if !expr.callee.is_specific_member_access("JSON", "parse") {
+        return false;
+    }
+    matches!(
+        expr.arguments.first().and_then(Argument::as_expression),
+        Some(Expression::CallExpression(inner)) if inner.callee.is_specific_member_access("JSON", "stringify")
+    )
+}
+
+/// Check if the `reduce` callback spreads its accumulator, e.g. `(acc, x) => ({ ...acc, [x.id]: x })`
+fn spreads_accumulator(expr: &CallExpression<'_>) -> bool {
+    let Some(callback) = expr.arguments.first().and_then(Argument::as_expression) else {
+        return false;
+    };
+    let accumulator = match callback {
+        Expression::ArrowFunctionExpression(arrow) => arrow.params.items.first(),
+        Expression::FunctionExpression(func) => func.params.items.first(),
+        _ => None,
+    };
+    let Some(accumulator) = accumulator.and_then(|param| param.pattern.get_identifier()) else {
+        return false;
+    };
+    let mut finder = SpreadFinder {
+        name: accumulator.as_str(),
+        found: false,
+    };
+    finder.visit_expression(callback);
+    finder.found
+}
+
+/// Check if the expression is a string literal, a template literal or a concatenation with one
+fn is_string_like(expr: &Expression<'_>) -> bool {
+    match expr.get_inner_expression() {
+        Expression::StringLiteral(_) | Expression::TemplateLiteral(_) => true,
+        Expression::BinaryExpression(binary) => {
+            is_string_like(&binary.left) || is_string_like(&binary.right)
+        }
+        _ => false,
+    }
+}
+
+/// Check if the expression copies an iterable into an array: `Array.from(x)` or `[...x]`
+fn is_array_copy(expr: &Expression<'_>) -> bool {
+    match expr.get_inner_expression() {
+        Expression::CallExpression(call) => {
+            call.callee.is_specific_member_access("Array", "from") && call.arguments.len() == 1
+        }
+        Expression::ArrayExpression(array) => {
+            array.elements.len() == 1
+                && matches!(array.elements[0], ArrayExpressionElement::SpreadElement(_))
+        }
+        _ => false,
+    }
+}
+
+/// Visitor that records whether the identifier with the given name is spread
+struct SpreadFinder<'n> {
+    name: &'n str,
+    found: bool,
+}
+
+impl<'a> Visit<'a> for SpreadFinder<'_> {
+    fn visit_spread_element(&mut self, spread: &SpreadElement<'a>) {
+        if spread.argument.is_specific_id(self.name) {
+            self.found = true;
+        }
+        walk_spread_element(self, spread);
+    }
+}
+
+// endregion: helpers
+// region: tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::rules::js::apply_to_source;
+
+    #[test]
+    fn test_json_deep_clone() {
+        let source_text = "const copy = JSON.parse(JSON.stringify(state));
+            const parsed = JSON.parse(response);";
+        let result = apply_to_source(&JsonDeepClone, source_text);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].column, 14);
+    }
+
+    #[test]
+    fn test_string_concat_in_loop() {
+        let source_text = "let html = '';
+            for (const item of items) { html += render(item); }
+            let total = 0;
+            for (const item of items) { total += item.price; }
+            items.forEach(item => { label += `<li>${item}</li>`; });
+            let once = ''; once += 'not in a loop';";
+        let result = apply_to_source(&StringConcatInLoop, source_text);
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].line, 2);
+        assert_eq!(result[1].line, 5);
+    }
+
+    #[test]
+    fn test_regexp_in_loop() {
+        let source_text = "const pattern = new RegExp(term, 'i');
+            for (const word of words) {
+                if (new RegExp(term, 'i').test(word)) count++;
+            }
+            function matcher() { return new RegExp(term); }";
+        let result = apply_to_source(&RegExpInLoop, source_text);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].line, 3);
+    }
+
+    #[test]
+    fn test_reduce_spread() {
+        let source_text =
+            "const byId = users.reduce((acc, user) => ({ ...acc, [user.id]: user }), {});
+            const all = lists.reduce(function (acc, list) { return [...acc, ...list]; }, []);
+            const sum = prices.reduce((acc, price) => acc + price, 0);";
+        let result = apply_to_source(&ReduceSpread, source_text);
+        assert_eq!(result.len(), 2);
+    }
+
+    #[test]
+    fn test_array_from_length() {
+        let source_text = "const count = Array.from(nodes).length;
+            const unique = [...new Set(ids)].length;
+            const size = nodes.length;";
+        let result = apply_to_source(&ArrayFromLength, source_text);
+        assert_eq!(result.len(), 2);
+        assert!(matches!(result[0].severity, Severity::Info));
+    }
+}
+// endregion: tests
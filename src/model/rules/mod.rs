@@ -2,7 +2,10 @@ use self::css::minify::Minify;
 
 pub mod css;
 pub mod html;
+pub mod inline_resources;
 pub mod js;
+pub mod third_party;
+pub mod web_fonts;
 use crate::model::ctx::Ctx;
 
 /// Rule trait that all rules must implement
@@ -40,14 +43,33 @@ impl std::fmt::Display for Severity {
 /// load_css_rules loads the css rules based on the rules to load
 /// new rules have to be added to the rules vector
 pub fn load_css_rules(rules_to_load: Vec<String>) -> Vec<Box<dyn Rule>> {
-    let rules = vec![Box::new(Minify) as Box<dyn Rule>];
+    let rules = vec![
+        Box::new(Minify) as Box<dyn Rule>,
+        Box::new(web_fonts::WebFonts::default()) as Box<dyn Rule>,
+        Box::new(inline_resources::DataUris::default()) as Box<dyn Rule>,
+    ];
     filter_rules(rules_to_load, rules)
 }
 
 /// load_html_rules loads the html rules based on the rules to load
 /// new rules have to be added to the rules vector
 pub fn load_html_rules(rules_to_load: Vec<String>) -> Vec<Box<dyn Rule>> {
-    let rules = vec![Box::new(html::loading::Loading) as Box<dyn Rule>];
+    let rules = vec![
+        Box::new(html::loading::Loading::default()) as Box<dyn Rule>,
+        Box::new(html::images::ModernImageFormats) as Box<dyn Rule>,
+        Box::new(html::images::ImageDimensions) as Box<dyn Rule>,
+        Box::new(html::images::ResponsiveImages::default()) as Box<dyn Rule>,
+        Box::new(html::render_blocking::RenderBlocking::default()) as Box<dyn Rule>,
+        Box::new(html::media::MediaElements) as Box<dyn Rule>,
+        Box::new(html::media::AnimatedGif) as Box<dyn Rule>,
+        Box::new(html::embeds::EmbedFacades) as Box<dyn Rule>,
+        Box::new(web_fonts::WebFonts::default()) as Box<dyn Rule>,
+        Box::new(third_party::ThirdParty::default()) as Box<dyn Rule>,
+        Box::new(html::dom_size::DomSize::default()) as Box<dyn Rule>,
+        Box::new(inline_resources::DataUris::default()) as Box<dyn Rule>,
+        Box::new(inline_resources::InlineBlocks::default()) as Box<dyn Rule>,
+        Box::new(inline_resources::RepeatedSvgs::default()) as Box<dyn Rule>,
+    ];
     filter_rules(rules_to_load, rules)
 }
 
@@ -56,7 +78,26 @@ pub fn load_html_rules(rules_to_load: Vec<String>) -> Vec<Box<dyn Rule>> {
 pub fn load_js_rules(rules_to_load: Vec<String>) -> Vec<Box<dyn Rule>> {
     let rules = vec![
         Box::new(js::minify::Minify) as Box<dyn Rule>,
-        Box::new(js::duplicates::Duplicates::default()) as Box<dyn Rule>,
+        Box::new(js::duplicates::Duplicates) as Box<dyn Rule>,
+        Box::new(js::unthrottled_listeners::UnthrottledListeners::default()) as Box<dyn Rule>,
+        Box::new(js::polling::Polling::default()) as Box<dyn Rule>,
+        Box::new(js::sequential_await::SequentialAwait::default()) as Box<dyn Rule>,
+        Box::new(js::heavy_imports::HeavyImports::default()) as Box<dyn Rule>,
+        Box::new(js::wasteful_idioms::JsonDeepClone) as Box<dyn Rule>,
+        Box::new(js::wasteful_idioms::StringConcatInLoop) as Box<dyn Rule>,
+        Box::new(js::wasteful_idioms::RegExpInLoop) as Box<dyn Rule>,
+        Box::new(js::wasteful_idioms::ReduceSpread) as Box<dyn Rule>,
+        Box::new(js::wasteful_idioms::ArrayFromLength) as Box<dyn Rule>,
+        Box::new(js::console_logging::ConsoleLogging::default()) as Box<dyn Rule>,
+        Box::new(js::blocking_apis::BlockingApis) as Box<dyn Rule>,
+        Box::new(js::timer_animation::TimerAnimation::default()) as Box<dyn Rule>,
+        Box::new(js::leaks::Leaks) as Box<dyn Rule>,
+        Box::new(js::dead_code::DeadCode) as Box<dyn Rule>,
+        Box::new(js::array_chains::ArrayChains) as Box<dyn Rule>,
+        Box::new(js::autoplay_media::AutoplayMedia) as Box<dyn Rule>,
+        Box::new(js::unbounded_caches::UnboundedCaches) as Box<dyn Rule>,
+        Box::new(js::heavy_computation::HeavyComputation::default()) as Box<dyn Rule>,
+        Box::new(third_party::ThirdParty::default()) as Box<dyn Rule>,
         // Add new rules here
         // Box::new(js::template_rule::TemplateRule) as Box<dyn Rule>,
     ];
@@ -72,3 +113,22 @@ fn filter_rules(rules_to_load: Vec<String>, rules: Vec<Box<dyn Rule>>) -> Vec<Bo
         .filter(|rule| rules_to_load.contains(&rule.get_name().to_lowercase()))
         .collect()
 }
+
+// region: helpers
+
+/// Get the line and column (both starting at 1) of a given byte offset in the given input string
+pub fn line_column(input: &str, start: u32) -> (i32, i32) {
+    let start = (start as usize).min(input.len());
+    let before = input.get(..start).unwrap_or(input);
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rsplit('\n')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .count()
+        + 1;
+    (line as i32, column as i32)
+}
+
+// endregion: helpers
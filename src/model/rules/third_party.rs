@@ -0,0 +1,293 @@
+use crate::model::ctx::Ctx;
+use crate::model::rules::LineResult;
+use crate::model::rules::Severity;
+
+use oxc::ast::ast::{Argument, CallExpression, Expression};
+use oxc::ast::visit::walk::walk_call_expression;
+use oxc::ast::Visit;
+
+use super::{line_column, Rule};
+
+/// the list of third-party domains, one domain and its category per line
+/// it is compiled into the binary, so changes to the file need a rebuild
+const DOMAIN_LIST: &str = include_str!("third_party_domains.txt");
+
+/// a third-party domain and what it is used for, e.g. analytics or ads
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Domain {
+    pub domain: String,
+    pub category: String,
+}
+
+/// a url to a third-party origin found in the input
+struct Reference {
+    host: String,
+    /// what loads the url: script, iframe, img or fetch
+    kind: &'static str,
+    position: (i32, i32),
+}
+
+/// a known origin with its first reference and the number of references of each kind
+struct Origin<'d> {
+    domain: &'d Domain,
+    first: Reference,
+    counts: Vec<(&'static str, usize)>,
+}
+
+/// This rule is used to find analytics, ads and social embeds loaded from third-party origins
+#[derive(Debug)]
+pub struct ThirdParty {
+    pub domains: Vec<Domain>,
+}
+
+impl Default for ThirdParty {
+    fn default() -> Self {
+        ThirdParty::from_list(DOMAIN_LIST)
+    }
+}
+
+impl Rule for ThirdParty {
+    fn get_name(&self) -> &str {
+        "Third-Party"
+    }
+
+    fn get_description(&self) -> &str {
+        "analytics, ads and social embeds add requests, transferred bytes and main thread work. remove the ones that are not needed and load the others after the page is interactive."
+    }
+
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        let references = match ctx {
+            Ctx::HtmlCtx(html_ctx) => {
+                let document = html_ctx.document;
+                document
+                    .elements
+                    .iter()
+                    .filter_map(|element| {
+                        let kind = match element.name.as_str() {
+                            "script" => "script",
+                            "iframe" => "iframe",
+                            "img" => "img",
+                            _ => return None,
+                        };
+                        Some(Reference {
+                            host: host(element.attr("src")?)?,
+                            kind,
+                            position: document.position(element.span.start),
+                        })
+                    })
+                    .collect()
+            }
+            Ctx::JavaScriptCtx(js_ctx) => {
+                let mut finder = FetchFinder { urls: vec![] };
+                finder.visit_program(js_ctx.program);
+                finder
+                    .urls
+                    .into_iter()
+                    .filter_map(|(url, start)| {
+                        Some(Reference {
+                            host: host(&url)?,
+                            kind: "fetch",
+                            position: line_column(js_ctx.input, start),
+                        })
+                    })
+                    .collect()
+            }
+            Ctx::CssCtx(_) => return None,
+        };
+        Some(self.group_by_origin(references))
+    }
+}
+
+impl ThirdParty {
+    /// Read a domain list with one domain and its category per line, lines starting with `#` are comments
+    pub fn from_list(list: &str) -> Self {
+        let domains = list
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut parts = line.split_whitespace();
+                Some(Domain {
+                    domain: parts.next()?.to_ascii_lowercase(),
+                    category: parts.next().unwrap_or("third-party").to_string(),
+                })
+            })
+            .collect();
+        ThirdParty { domains }
+    }
+
+    /// Get the known domain of the host, subdomains like `www.` match their domain
+    fn domain(&self, host: &str) -> Option<&Domain> {
+        self.domains.iter().find(|domain| {
+            host == domain.domain
+                || host
+                    .strip_suffix(domain.domain.as_str())
+                    .is_some_and(|subdomain| subdomain.ends_with('.'))
+        })
+    }
+
+    /// Create one result per known origin at its first reference, with the number of references of each kind
+    fn group_by_origin(&self, references: Vec<Reference>) -> Vec<LineResult> {
+        // known origins in the order of their first reference
+        let mut origins: Vec<Origin> = vec![];
+        for reference in references {
+            let Some(domain) = self.domain(&reference.host) else {
+                continue;
+            };
+            let kind = reference.kind;
+            let index = match origins
+                .iter()
+                .position(|origin| origin.first.host == reference.host)
+            {
+                Some(index) => index,
+                None => {
+                    origins.push(Origin {
+                        domain,
+                        first: reference,
+                        counts: vec![],
+                    });
+                    origins.len() - 1
+                }
+            };
+            let counts = &mut origins[index].counts;
+            match counts.iter_mut().find(|(counted, _)| *counted == kind) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((kind, 1)),
+            }
+        }
+
+        origins
+            .into_iter()
+            .map(|origin| {
+                let total: usize = origin.counts.iter().map(|(_, count)| count).sum();
+                let kinds: Vec<String> = origin
+                    .counts
+                    .iter()
+                    .map(|(kind, count)| format!("{} {}", count, kind))
+                    .collect();
+                LineResult {
+                    severity: Severity::Warning,
+                    line: origin.first.position.0,
+                    column: origin.first.position.1,
+                    classification: self.get_name().to_string(),
+                    description: format!(
+                        "'{}' ({}) is referenced {} time(s): {}. {}",
+                        origin.first.host,
+                        origin.domain.category,
+                        total,
+                        kinds.join(", "),
+                        self.get_description()
+                    ),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Visitor that finds `fetch` calls with a literal url
+struct FetchFinder {
+    urls: Vec<(String, u32)>,
+}
+
+impl<'a> Visit<'a> for FetchFinder {
+    fn visit_call_expression(&mut self, expr: &CallExpression<'a>) {
+        if is_fetch(&expr.callee) {
+            let url = match expr.arguments.first() {
+                Some(Argument::StringLiteral(url)) => Some(url.value.to_string()),
+                // `fetch(`https://api.example.com/${path}`)`
+                Some(Argument::TemplateLiteral(url)) => {
+                    url.quasis.first().map(|quasi| quasi.value.raw.to_string())
+                }
+                _ => None,
+            };
+            if let Some(url) = url {
+                self.urls.push((url, expr.span.start));
+            }
+        }
+        walk_call_expression(self, expr);
+    }
+}
+
+// region: helpers
+
+/// Check if the callee is `fetch` or `window.fetch`
+fn is_fetch(callee: &Expression<'_>) -> bool {
+    if callee.is_specific_id("fetch") {
+        return true;
+    }
+    callee.as_member_expression().is_some_and(|member| {
+        member.static_property_name() == Some("fetch")
+            && matches!(member.object(), Expression::Identifier(object) if matches!(object.name.as_str(), "window" | "self" | "globalThis"))
+    })
+}
+
+/// Get the lowercase host of an absolute or protocol-relative url
+fn host(url: &str) -> Option<String> {
+    let url = url.trim();
+    let rest = url
+        .strip_prefix("//")
+        .or_else(|| url.split_once("://").map(|(_, rest)| rest))?;
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = authority.rsplit('@').next().unwrap_or_default();
+    let host = host.split(':').next().unwrap_or_default();
+    (!host.is_empty()).then(|| host.to_ascii_lowercase())
+}
+
+// endregion: helpers
+// region: tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::rules::html;
+    use crate::model::rules::js;
+
+    #[test]
+    fn test_stack_overflow_page() {
+        let html = include_str!("../../../testfiles/img_iframe_html_test");
+        let result = html::apply_to_source(&ThirdParty::default(), html);
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].line, 3357);
+        assert!(result[0].description.starts_with(
+            "'www.googletagmanager.com' (analytics) is referenced 1 time(s): 1 script."
+        ));
+        assert!(result[1].description.contains("(consent)"));
+    }
+
+    #[test]
+    fn test_references_are_grouped_per_origin() {
+        let html = "<script src=\"https://connect.facebook.net/en_US/sdk.js\"></script>
+<img src=\"//www.facebook.com/tr?id=1&ev=PageView\" width=\"1\" height=\"1\">
+<img src=\"https://www.facebook.com/tr?id=1&ev=Purchase\">
+<img src=\"/images/facebook.com.png\">
+<iframe src=\"https://ads.doubleclick.net:443/frame\"></iframe>
+<script src=\"https://notfacebook.com/sdk.js\"></script>";
+        let result = html::apply_to_source(&ThirdParty::default(), html);
+        assert_eq!(result.len(), 3);
+        assert!(result[0]
+            .description
+            .starts_with("'connect.facebook.net' (social) is referenced 1 time(s): 1 script."));
+        assert_eq!(result[1].line, 2);
+        assert!(result[1]
+            .description
+            .starts_with("'www.facebook.com' (social) is referenced 2 time(s): 2 img."));
+        assert!(result[2].description.contains("(ads)"));
+    }
+
+    #[test]
+    fn test_fetch_urls_and_custom_lists() {
+        let source_text = "fetch('https://api.mixpanel.com/track', { method: 'POST' });
+            window.fetch(`https://api.example.com/${path}`);
+            fetch('/api/local');
+            fetch(url);";
+        let result = js::apply_to_source(&ThirdParty::default(), source_text);
+        assert_eq!(result.len(), 1);
+        assert!(result[0].description.contains("1 fetch"));
+
+        let rule = ThirdParty::from_list("# internal\nexample.com telemetry");
+        let result = js::apply_to_source(&rule, source_text);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].line, 2);
+        assert!(result[0].description.contains("(telemetry)"));
+    }
+}
+// endregion: tests
@@ -0,0 +1,467 @@
+use crate::model::ctx::Ctx;
+use crate::model::rules::LineResult;
+use crate::model::rules::Severity;
+
+use super::{line_column, Rule};
+
+/// font services that use the google fonts url format
+const FONT_SERVICES: [&str; 2] = ["fonts.googleapis.com/css", "fonts.bunny.net/css"];
+
+/// a font file the page may download
+struct Face {
+    family: String,
+    /// weight and style, e.g. `400` or `700 italic`
+    variant: String,
+    /// byte offset of the `@font-face` or `<link>` that declares it
+    offset: usize,
+}
+
+/// the faces and findings collected from the input
+#[derive(Default)]
+struct Fonts {
+    faces: Vec<Face>,
+    findings: Vec<(usize, Severity, String)>,
+}
+
+/// This rule is used to find web fonts that are too many, too large or block the text of the page
+#[derive(Debug)]
+pub struct WebFonts {
+    /// more font families than this are reported
+    pub max_families: usize,
+    /// more weights and styles of a single family than this are reported
+    pub max_variants: usize,
+}
+
+impl Default for WebFonts {
+    fn default() -> Self {
+        WebFonts {
+            max_families: 3,
+            max_variants: 4,
+        }
+    }
+}
+
+impl Rule for WebFonts {
+    fn get_name(&self) -> &str {
+        "Web-Fonts"
+    }
+
+    fn get_description(&self) -> &str {
+        "every font family, weight and style is a separate download, and text using it may stay invisible until it is loaded."
+    }
+
+    fn apply(&self, ctx: &Ctx<'_>) -> Option<Vec<LineResult>> {
+        let mut fonts = Fonts::default();
+        let result = match ctx {
+            Ctx::HtmlCtx(html_ctx) => {
+                let document = html_ctx.document;
+                for element in &document.elements {
+                    match element.name.as_str() {
+                        "link" => {
+                            if let Some(href) = element.attr("href") {
+                                let source = document.source(element);
+                                fonts.read_font_service(href, source, element.span.start);
+                            }
+                        }
+                        "style" => {
+                            if let (Some(css), Some(content)) =
+                                (document.content(element), &element.content)
+                            {
+                                fonts.read_font_faces(css, content.start);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                self.report(fonts, |offset| document.position(offset))
+            }
+            Ctx::CssCtx(css_ctx) => {
+                fonts.read_font_faces(css_ctx.input, 0);
+                self.report(fonts, |offset| line_column(css_ctx.input, offset as u32))
+            }
+            Ctx::JavaScriptCtx(_) => return None,
+        };
+        Some(result)
+    }
+}
+
+impl WebFonts {
+    /// Add the findings for too many families and variants and create the results in source order
+    fn report(&self, mut fonts: Fonts, position: impl Fn(usize) -> (i32, i32)) -> Vec<LineResult> {
+        // families in the order of their first face, with their distinct variants
+        let mut families: Vec<(&str, usize, Vec<&str>)> = vec![];
+        for face in &fonts.faces {
+            match families
+                .iter_mut()
+                .find(|(family, _, _)| family.eq_ignore_ascii_case(&face.family))
+            {
+                Some((_, _, variants)) => {
+                    if !variants.contains(&face.variant.as_str()) {
+                        variants.push(&face.variant);
+                    }
+                }
+                None => families.push((&face.family, face.offset, vec![&face.variant])),
+            }
+        }
+
+        let mut findings = vec![];
+        if families.len() > self.max_families {
+            let names: Vec<String> = families
+                .iter()
+                .map(|(family, _, _)| format!("'{}'", family))
+                .collect();
+            findings.push((
+                families[self.max_families].1,
+                Severity::Warning,
+                format!(
+                    "the page loads {} font families ({}), this is number {}. use at most {} families",
+                    families.len(),
+                    names.join(", "),
+                    self.max_families + 1,
+                    self.max_families
+                ),
+            ));
+        }
+        for (family, offset, variants) in &families {
+            if variants.len() > self.max_variants {
+                findings.push((
+                    *offset,
+                    Severity::Warning,
+                    format!(
+                        "font family '{}' is loaded in {} weights and styles ({}). use at most {} or a variable font",
+                        family,
+                        variants.len(),
+                        variants.join(", "),
+                        self.max_variants
+                    ),
+                ));
+            }
+        }
+        fonts.findings.extend(findings);
+        fonts.findings.sort_by_key(|(offset, _, _)| *offset);
+
+        fonts
+            .findings
+            .into_iter()
+            .map(|(offset, severity, message)| {
+                let (line, column) = position(offset);
+                LineResult {
+                    severity,
+                    line,
+                    column,
+                    classification: self.get_name().to_string(),
+                    description: format!("{}. {}", message, self.get_description()),
+                }
+            })
+            .collect()
+    }
+}
+
+impl Fonts {
+    /// Read the families of a font service stylesheet like `https://fonts.googleapis.com/css2?family=Roboto:wght@400;700`
+    fn read_font_service(&mut self, href: &str, source: &str, offset: usize) {
+        let href = href.replace("&amp;", "&");
+        if !FONT_SERVICES.iter().any(|service| href.contains(service)) {
+            return;
+        }
+        let query = href
+            .split_once('?')
+            .map(|(_, query)| query)
+            .unwrap_or_default();
+        let mut display = false;
+        for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+            match key {
+                "display" => display = true,
+                "family" => {
+                    // css2 uses one family parameter per family, the old api separates them with `|`
+                    for family in value.split('|') {
+                        self.read_service_family(family, offset);
+                    }
+                }
+                _ => {}
+            }
+        }
+        if !display {
+            self.findings.push((
+                offset,
+                Severity::Warning,
+                format!(
+                    "'{}' has no display parameter, so text stays invisible until the fonts are loaded. add &display=swap",
+                    source
+                ),
+            ));
+        }
+    }
+
+    /// Read a family parameter like `Roboto:ital,wght@0,400;1,700` or `Open+Sans:400,700italic`
+    fn read_service_family(&mut self, family: &str, offset: usize) {
+        let family = family.replace('+', " ").replace("%20", " ");
+        let (name, variants) = family.split_once(':').unwrap_or((&family, ""));
+        let variants: Vec<String> = match variants.split_once('@') {
+            // css2: the axes are listed before `@`, each tuple after it is one face
+            Some((axes, tuples)) => tuples
+                .split(';')
+                .map(|tuple| {
+                    let italic = axes.split(',').position(|axis| axis == "ital");
+                    let values: Vec<&str> = tuple.split(',').collect();
+                    let weight = axes
+                        .split(',')
+                        .position(|axis| axis == "wght")
+                        .and_then(|index| values.get(index))
+                        .copied()
+                        .unwrap_or("400")
+                        .replace("..", "-");
+                    match italic.and_then(|index| values.get(index)) {
+                        Some(&"1") => format!("{} italic", weight),
+                        _ => weight,
+                    }
+                })
+                .collect(),
+            None if variants.is_empty() => vec!["400".to_string()],
+            // the old api lists variants like `400,700italic`
+            None => variants
+                .split(',')
+                .map(|variant| match variant.strip_suffix("italic") {
+                    Some("") => "400 italic".to_string(),
+                    Some(weight) => format!("{} italic", weight),
+                    None => variant.to_string(),
+                })
+                .collect(),
+        };
+        for variant in variants {
+            self.faces.push(Face {
+                family: name.trim().to_string(),
+                variant,
+                offset,
+            });
+        }
+    }
+
+    /// Read the `@font-face` rules of a stylesheet, the offsets are relative to `base`
+    fn read_font_faces(&mut self, css: &str, base: usize) {
+        let lowercase = css.to_ascii_lowercase();
+        for (start, _) in lowercase.match_indices("@font-face") {
+            let Some(open) = css[start..].find('{').map(|open| start + open + 1) else {
+                continue;
+            };
+            let close = css[open..]
+                .find('}')
+                .map_or(css.len(), |close| open + close);
+            let declarations = declarations(&css[open..close]);
+            let value = |property: &str| {
+                declarations
+                    .iter()
+                    .find(|(name, _)| name == property)
+                    .map(|(_, value)| value.as_str())
+            };
+            let family = value("font-family")
+                .unwrap_or_default()
+                .trim_matches(['"', '\''])
+                .to_string();
+            let offset = base + start;
+            let subject = format!("@font-face for '{}'", family);
+
+            if value("font-display").is_none() {
+                self.findings.push((
+                    offset,
+                    Severity::Warning,
+                    format!(
+                        "{} has no font-display, so text stays invisible until the font is loaded. add font-display: swap",
+                        subject
+                    ),
+                ));
+            }
+            let formats = formats(value("src").unwrap_or_default());
+            if !formats.is_empty() && !formats.iter().any(|format| format == "woff2") {
+                self.findings.push((
+                    offset,
+                    Severity::Warning,
+                    format!(
+                        "{} offers no WOFF2 file, only {}. WOFF2 is about 30% smaller than WOFF and much smaller than TTF or OTF",
+                        subject,
+                        formats.join(", ")
+                    ),
+                ));
+            }
+            if value("unicode-range").is_none() {
+                self.findings.push((
+                    offset,
+                    Severity::Info,
+                    format!(
+                        "{} has no unicode-range, so the whole font is downloaded for any text. split it into subsets and declare the unicode-range of each",
+                        subject
+                    ),
+                ));
+            }
+
+            let weight = match value("font-weight").map(str::to_ascii_lowercase).as_deref() {
+                None | Some("normal") => "400".to_string(),
+                Some("bold") => "700".to_string(),
+                Some(weight) => weight.replace(' ', "-"),
+            };
+            let variant = match value("font-style").map(str::to_ascii_lowercase).as_deref() {
+                Some("italic" | "oblique") => format!("{} italic", weight),
+                _ => weight,
+            };
+            self.faces.push(Face {
+                family,
+                variant,
+                offset,
+            });
+        }
+    }
+}
+
+// region: helpers
+
+/// Split a declaration block into lowercase property names and values
+/// semicolons inside quotes and parentheses, like in `url(data:font/woff2;base64,...)`, do not end a declaration
+fn declarations(block: &str) -> Vec<(String, String)> {
+    let mut declarations = vec![];
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut start = 0;
+    for (index, c) in block.char_indices() {
+        match (quote, c) {
+            (Some(open), _) if c == open => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => depth = depth.saturating_sub(1),
+            (None, ';') if depth == 0 => {
+                declarations.extend(declaration(&block[start..index]));
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    declarations.extend(declaration(&block[start..]));
+    declarations
+}
+
+fn declaration(text: &str) -> Option<(String, String)> {
+    let (property, value) = text.split_once(':')?;
+    Some((
+        property.trim().to_ascii_lowercase(),
+        value.trim().to_string(),
+    ))
+}
+
+/// Get the formats of the font files in a `src` descriptor, from `format()` or the file extension
+fn formats(src: &str) -> Vec<String> {
+    let mut formats: Vec<String> = vec![];
+    for source in src.split("url(").skip(1) {
+        let (url, rest) = source.split_once(')').unwrap_or((source, ""));
+        let url = url.trim().trim_matches(['"', '\'']).to_ascii_lowercase();
+        let hint = rest
+            .split(',')
+            .next()
+            .and_then(|hint| hint.trim().strip_prefix("format("))
+            .map(|hint| {
+                hint.trim_end_matches(')')
+                    .trim_matches(['"', '\''])
+                    .to_ascii_lowercase()
+            });
+        let format = hint.or_else(|| {
+            if let Some(data) = url.strip_prefix("data:") {
+                return data
+                    .split([';', ','])
+                    .next()
+                    .and_then(|mime| mime.rsplit(['/', '-']).next())
+                    .map(str::to_string);
+            }
+            let path = url.split(['?', '#']).next().unwrap_or_default();
+            path.rsplit_once('.')
+                .map(|(_, extension)| extension.to_string())
+        });
+        let format = match format.as_deref() {
+            Some("truetype") => "ttf".to_string(),
+            Some("opentype") => "otf".to_string(),
+            Some("embedded-opentype") => "eot".to_string(),
+            Some(format) => format.to_string(),
+            None => continue,
+        };
+        if !formats.contains(&format) {
+            formats.push(format);
+        }
+    }
+    formats
+}
+
+// endregion: helpers
+// region: tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ctx::CssCtx;
+    use crate::model::rules::html;
+
+    fn apply_to_css(rule: &WebFonts, input: &str) -> Vec<LineResult> {
+        rule.apply(&Ctx::CssCtx(CssCtx { input }))
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_font_faces_in_css() {
+        let css = "body { font-family: 'Inter', sans-serif; }
+@font-face {
+  font-family: 'Inter';
+  src: url('/fonts/inter.woff2') format('woff2'), url('/fonts/inter.woff') format('woff');
+  font-display: swap;
+  unicode-range: U+0000-00FF;
+}
+@font-face {
+  font-family: \"Lobster\";
+  font-weight: bold;
+  src: url(data:font/ttf;base64,AAEAAAAL) format(\"truetype\"), url(/fonts/lobster.otf);
+}";
+        let result = apply_to_css(&WebFonts::default(), css);
+        assert_eq!(result.len(), 3);
+        assert_eq!((result[0].line, result[0].column), (8, 1));
+        assert!(result[0]
+            .description
+            .starts_with("@font-face for 'Lobster' has no font-display"));
+        assert!(result[1]
+            .description
+            .contains("offers no WOFF2 file, only ttf, otf"));
+        assert!(matches!(result[2].severity, Severity::Info));
+    }
+
+    #[test]
+    fn test_font_services_in_html() {
+        let html = "<head>
+<link href=\"https://fonts.googleapis.com/css2?family=Roboto:ital,wght@0,300;0,400;0,700;1,400;1,700&amp;family=Open+Sans&amp;display=swap\" rel=\"stylesheet\">
+<link href=\"https://fonts.googleapis.com/css?family=Lato:400,700italic|Oswald\" rel=\"stylesheet\">
+</head>";
+        let result = html::apply_to_source(&WebFonts::default(), html);
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[0].line, 2);
+        assert!(result[0].description.starts_with(
+            "font family 'Roboto' is loaded in 5 weights and styles (300, 400, 700, 400 italic, 700 italic)"
+        ));
+        assert_eq!(result[1].line, 3);
+        assert!(result[1].description.contains("has no display parameter"));
+        assert!(result[2].description.starts_with(
+            "the page loads 4 font families ('Roboto', 'Open Sans', 'Lato', 'Oswald'), this is number 4"
+        ));
+    }
+
+    #[test]
+    fn test_font_faces_in_style_elements() {
+        let html = "<style>
+  @font-face { font-family: Icons; src: url(icons.woff2); font-display: block; unicode-range: U+E000-E0FF }
+  @FONT-FACE { font-family: Icons; src: url(icons.woff2); font-display: block; unicode-range: U+E000-E0FF }
+</style>";
+        let result = html::apply_to_source(&WebFonts::default(), html);
+        assert!(result.is_empty());
+
+        let rule = WebFonts {
+            max_families: 0,
+            max_variants: 4,
+        };
+        let result = html::apply_to_source(&rule, html);
+        assert_eq!(result.len(), 1);
+        assert_eq!((result[0].line, result[0].column), (2, 3));
+    }
+}
+// endregion: tests